@@ -0,0 +1,267 @@
+use crate::interpreter::tokens::{
+    dictionary::{Dictionary, STANDARD},
+    indentation::Indentation,
+};
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::PathBuf,
+};
+
+pub const USAGE: &str = "\
+使い方:
+    shikibu                                 対話モードを始める
+    shikibu [オプション] <ファイル> [引数...]
+    shikibu [オプション] -e <コード> [引数...]
+    shikibu [オプション] - [引数...]
+    shikibu check [オプション] <ファイル>     実行せずにまちがいを探す
+    shikibu doc [オプション] <ファイル>       関数の一覧と説明（＃＃ のコメント）を表示する
+
+オプション:
+    -e <コード>      コードを直接実行する
+    -               標準入力からプログラムを読み込む
+    --color <いつ>   エラーを色付きで表示するか（auto, always, never）
+    --message-format <形式>
+                    エラーの表示形式（human, json）。json では一件ずつ一行の JSON で表示する
+    --tab-width <桁数>
+                    字下げのタブを何桁として数えるか（初期値 4）
+    --full-width-space <桁数>
+                    字下げの全角スペースを何桁として数えるか（1 か 2、初期値 2）
+    --no-mixed-indent
+                    字下げに半角スペース、全角スペース、タブを混ぜたらエラーにする
+    --keywords <辞書>
+                    キーワードの辞書（標準, ひらがな, 英語）。ファイルの先頭の
+                    「＃ キーワード：ひらがな」のようなコメントが優先される
+    --explain <番号> エラー番号（S0003 や R0005 など）のくわしい説明を表示する
+    -h, --help      この使い方を表示する
+    -V, --version   バージョンを表示する
+
+プログラムのあとに書いた引数は、配列「引数」としてプログラムから使えます。";
+
+#[derive(Debug)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+    Inline(String),
+}
+
+impl Input {
+    /// The name shown to the user when referring to this input.
+    pub fn name(&self) -> String {
+        match self {
+            Input::File(path) => path.display().to_string(),
+            Input::Stdin => "<標準入力>".to_string(),
+            Input::Inline(_) => "<-e>".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Color {
+    /// Whether diagnostics written to the standard error should be colored.
+    /// `auto` colors only a terminal, and respects the `NO_COLOR` environment variable.
+    pub fn enabled(&self) -> bool {
+        match self {
+            Color::Auto => io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+            Color::Always => true,
+            Color::Never => false,
+        }
+    }
+}
+
+/// How diagnostics are written to the standard error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageFormat {
+    /// Messages with source excerpts for people.
+    Human,
+    /// One JSON object per line for tools.
+    Json,
+}
+
+#[derive(Debug)]
+pub struct Options {
+    /// The program to run. The interactive mode is started when it is `None`.
+    pub input: Option<Input>,
+    pub args: Vec<String>,
+    pub color: Color,
+    pub message_format: MessageFormat,
+    pub indentation: Indentation,
+    /// The keywords used unless the program selects others with a pragma.
+    pub keywords: &'static Dictionary,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Run(Options),
+    /// Look for mistakes in the program without running it.
+    Check(Options),
+    /// Print the functions in the program with their documentation comments.
+    Doc(Options),
+    /// Print the explanation of an error code.
+    Explain(String),
+    Help,
+    Version,
+}
+
+/// Parses the command line arguments, excluding the program name.
+/// Without an input the interactive mode is started. `check` or `doc` before the
+/// input selects that subcommand instead of running the program.
+///
+/// Everything after the input (a file, `-` or `-e <code>`) is passed through
+/// to the script untouched, even if it looks like an option.
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut input = None;
+    let mut color = Color::Auto;
+    let mut message_format = MessageFormat::Human;
+    let mut indentation = Indentation::default();
+    let mut keywords = &STANDARD;
+    let mut subcommand = None;
+
+    while input.is_none() {
+        let Some(arg) = args.next() else {
+            break;
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--explain" => match args.next() {
+                Some(code) => return Ok(Command::Explain(code)),
+                None => return Err("--explain のあとにエラー番号を指定してください".to_string()),
+            },
+            option if option.starts_with("--explain=") => {
+                let code = option.strip_prefix("--explain=").unwrap_or_default();
+                return Ok(Command::Explain(code.to_string()));
+            }
+            "--color" => color = parse_color(args.next())?,
+            option if option.starts_with("--color=") => {
+                color = parse_color(option.strip_prefix("--color=").map(String::from))?;
+            }
+            "--message-format" => message_format = parse_message_format(args.next())?,
+            option if option.starts_with("--message-format=") => {
+                let value = option.strip_prefix("--message-format=").map(String::from);
+                message_format = parse_message_format(value)?;
+            }
+            "--tab-width" => indentation.tab_width = parse_tab_width(args.next())?,
+            option if option.starts_with("--tab-width=") => {
+                let value = option.strip_prefix("--tab-width=").map(String::from);
+                indentation.tab_width = parse_tab_width(value)?;
+            }
+            "--full-width-space" => {
+                indentation.full_width_space = parse_full_width_space(args.next())?;
+            }
+            option if option.starts_with("--full-width-space=") => {
+                let value = option.strip_prefix("--full-width-space=").map(String::from);
+                indentation.full_width_space = parse_full_width_space(value)?;
+            }
+            "--no-mixed-indent" => indentation.allow_mixing = false,
+            "--keywords" => keywords = parse_keywords(args.next())?,
+            option if option.starts_with("--keywords=") => {
+                keywords = parse_keywords(option.strip_prefix("--keywords=").map(String::from))?;
+            }
+            "-e" => match args.next() {
+                Some(code) => input = Some(Input::Inline(code)),
+                None => return Err("-e のあとにコードを指定してください".to_string()),
+            },
+            "check" | "doc" if subcommand.is_none() => subcommand = Some(arg),
+            "-" => input = Some(Input::Stdin),
+            "--" => match args.next() {
+                Some(path) => input = Some(Input::File(PathBuf::from(path))),
+                None => return Err("実行するファイルを指定してください".to_string()),
+            },
+            option if option.starts_with('-') => {
+                return Err(format!("不明なオプションです: {}", option));
+            }
+            path => input = Some(Input::File(PathBuf::from(path))),
+        }
+    }
+
+    let options = Options {
+        input,
+        args: args.collect(),
+        color,
+        message_format,
+        indentation,
+        keywords,
+    };
+    match subcommand.as_deref() {
+        None => Ok(Command::Run(options)),
+        Some("check") if options.input.is_none() => {
+            Err("検査するファイルを指定してください".to_string())
+        }
+        Some(_) if options.input.is_none() => {
+            Err("説明を表示するファイルを指定してください".to_string())
+        }
+        Some("check") => Ok(Command::Check(options)),
+        _ => Ok(Command::Doc(options)),
+    }
+}
+
+fn parse_color(value: Option<String>) -> Result<Color, String> {
+    match value.as_deref() {
+        Some("auto") => Ok(Color::Auto),
+        Some("always") => Ok(Color::Always),
+        Some("never") => Ok(Color::Never),
+        Some(value) => Err(format!(
+            "--color には auto, always, never のどれかを指定してください: {}",
+            value
+        )),
+        None => Err("--color のあとに auto, always, never のどれかを指定してください".to_string()),
+    }
+}
+
+fn parse_message_format(value: Option<String>) -> Result<MessageFormat, String> {
+    match value.as_deref() {
+        Some("human") => Ok(MessageFormat::Human),
+        Some("json") => Ok(MessageFormat::Json),
+        Some(value) => Err(format!(
+            "--message-format には human, json のどちらかを指定してください: {}",
+            value
+        )),
+        None => {
+            Err("--message-format のあとに human, json のどちらかを指定してください".to_string())
+        }
+    }
+}
+
+fn parse_tab_width(value: Option<String>) -> Result<usize, String> {
+    match value.as_deref().map(str::parse::<usize>) {
+        Some(Ok(width)) if width > 0 => Ok(width),
+        Some(_) => Err(format!(
+            "--tab-width には 1 以上の数を指定してください: {}",
+            value.unwrap_or_default()
+        )),
+        None => Err("--tab-width のあとに桁数を指定してください".to_string()),
+    }
+}
+
+fn parse_full_width_space(value: Option<String>) -> Result<usize, String> {
+    match value.as_deref() {
+        Some("1") => Ok(1),
+        Some("2") => Ok(2),
+        Some(value) => Err(format!(
+            "--full-width-space には 1 か 2 を指定してください: {}",
+            value
+        )),
+        None => Err("--full-width-space のあとに 1 か 2 を指定してください".to_string()),
+    }
+}
+
+fn parse_keywords(value: Option<String>) -> Result<&'static Dictionary, String> {
+    let names = Dictionary::ALL.map(|dictionary| dictionary.name).join(", ");
+    match value.as_deref() {
+        Some(value) => Dictionary::named(value).ok_or(format!(
+            "--keywords には {} のどれかを指定してください: {}",
+            names, value
+        )),
+        None => Err(format!(
+            "--keywords のあとに {} のどれかを指定してください",
+            names
+        )),
+    }
+}
@@ -1,13 +1,34 @@
 use self::{
     ast::{node::Node, Ast},
-    error::RuntimeError,
+    error::{Frame, RuntimeError},
+    normalize::normalize,
+    span::Span,
+    tokens::{dictionary::Dictionary, keyword::Keyword},
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
 };
-use std::collections::{HashMap, VecDeque};
 
 pub mod ast;
+pub mod checker;
+pub mod cst;
+pub mod diagnostic;
 pub mod error;
+pub mod json;
+pub mod normalize;
+pub mod span;
+pub mod suggest;
 pub mod tokens;
 
+/// The deepest function calls may nest, so that endless recursion is reported as an
+/// error before it overflows the stack.
+const MAX_CALL_DEPTH: usize = 1000;
+
+/// The stack size of the thread a program must run on, large enough for
+/// [`MAX_CALL_DEPTH`] nested calls even in a debug build.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
 #[derive(Debug, Clone, PartialEq)]
 enum Value {
     Number(f64),
@@ -17,86 +38,222 @@ enum Value {
     Null,
 }
 
+impl Value {
+    /// The name of the type of the value, used in error messages.
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "数値",
+            Value::String(_) => "文字列",
+            Value::Array { .. } => "配列",
+            Value::Bool(_) => "真偽値",
+            Value::Null => "無",
+        }
+    }
+
+    /// Formats the value for a backtrace, quoting strings so that `”１”` and `１` differ.
+    fn describe(&self) -> String {
+        match self {
+            Value::String(string) => format!("”{}”", string),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{}", number),
+            Value::String(string) => write!(f, "{}", string),
+            Value::Array { elements, length } => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    write!(f, "{}", element)?;
+                    if i < *length {
+                        write!(f, ", ")?;
+                    }
+                }
+                write!(f, "]")
+            }
+            Value::Bool(true) => write!(f, "真"),
+            Value::Bool(false) => write!(f, "偽"),
+            Value::Null => write!(f, "無"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Interpreter {
     ast: Ast,
+    /// Functions and variables are keyed by their normalized names. Variables also keep
+    /// the spelling they were assigned with, which suggestions show.
     functions: HashMap<String, Node>,
-    variables: HashMap<String, Value>,
+    variables: HashMap<String, (String, Value)>,
+    /// Set for the REPL: bare expressions echo their value and functions may be redefined.
+    interactive: bool,
+    /// The dictionary the program's keywords are read in, which also spells them in errors.
+    dictionary: &'static Dictionary,
+    /// The number of function calls this interpreter runs inside of.
+    depth: usize,
 }
 
 impl Interpreter {
-    pub fn run(ast: Ast) -> Result<(), RuntimeError> {
+    /// Runs a whole program. `args` are exposed to the program as the array `引数`, and
+    /// `dictionary` is the one its keywords were read in.
+    pub fn run(
+        ast: Ast,
+        args: Vec<String>,
+        dictionary: &'static Dictionary,
+    ) -> Result<(), RuntimeError> {
+        let mut variables = HashMap::new();
+        variables.insert(
+            "引数".to_string(),
+            ("引数".to_string(), arguments_to_array(args)),
+        );
+
         let mut interpreter = Self {
             ast,
             functions: HashMap::new(),
-            variables: HashMap::new(),
+            variables,
+            interactive: false,
+            dictionary,
+            depth: 0,
         };
-        interpreter.drain_functions().unwrap();
-        interpreter.run_children().unwrap();
-        Ok(())
+        interpreter.drain_functions()?;
+        interpreter.run_children()
+    }
+
+    /// Creates an interpreter whose state survives between calls to [`Interpreter::execute`].
+    pub fn interactive(dictionary: &'static Dictionary) -> Self {
+        Self {
+            ast: Ast::new(Vec::new()),
+            functions: HashMap::new(),
+            variables: HashMap::new(),
+            interactive: true,
+            dictionary,
+            depth: 0,
+        }
+    }
+
+    /// Runs one more piece of a program against the current variables and functions.
+    pub fn execute(&mut self, ast: Ast) -> Result<(), RuntimeError> {
+        self.ast = ast;
+        self.drain_functions()?;
+        self.run_children()
     }
 
     fn run_children(&mut self) -> Result<(), RuntimeError> {
         while !self.ast.is_empty() {
             let node = self.ast.pop_front().unwrap();
             match node {
-                Node::Assignment { name, value } => _ = self.assign_variable(name, *value),
-                Node::IndexAssignment { name, index, value } => {
-                    self.assign_index(name, *index, *value).unwrap();
+                Node::Assignment { name, value, .. } => {
+                    self.assign_variable(name, *value)?;
+                }
+                Node::IndexAssignment {
+                    target,
+                    index,
+                    value,
+                    ..
+                } => {
+                    self.assign_index(*target, *index, *value)?;
+                }
+                Node::Call { .. } => {
+                    let value = self.call_function(node)?;
+                    self.echo(&value);
                 }
-                Node::Call { .. } => _ = self.call_function(node),
                 Node::If {
                     condition,
                     then_part,
                     else_part,
-                } => _ = self.run_if(*condition, then_part, else_part),
-                Node::Loop { body } => _ = self.run_loop(body),
-                Node::Print(value) => {
-                    let value = self.calculate(*value).unwrap();
+                    ..
+                } => {
+                    let node = self.run_if(*condition, then_part, else_part)?;
+                    match node {
+                        Node::Return(..) => {
+                            return Err(RuntimeError::return_outside_function(
+                                node,
+                                self.dictionary,
+                            ))
+                        }
+                        Node::Break(_) | Node::Continue(_) => {
+                            return Err(RuntimeError::break_outside_loop(node, self.dictionary));
+                        }
+                        _ => (),
+                    }
+                }
+                Node::Loop { body, .. } => {
+                    self.run_loop(body)?;
+                }
+                Node::Print(value, _) => {
+                    let value = self.calculate(*value)?;
                     self.println(&value);
                 }
+                Node::Return(..) => {
+                    return Err(RuntimeError::return_outside_function(node, self.dictionary))
+                }
+                Node::Break(_) | Node::Continue(_) => {
+                    return Err(RuntimeError::break_outside_loop(node, self.dictionary));
+                }
+                _ if self.interactive => {
+                    let value = self.calculate(node)?;
+                    self.echo(&value);
+                }
                 _ => return Err(RuntimeError::unexpected_node(node)),
             }
         }
         Ok(())
     }
 
+    /// Prints the value of a bare expression in the REPL, the same way `表示` would.
+    fn echo(&self, value: &Value) {
+        if self.interactive && value != &Value::Null {
+            self.println(value);
+        }
+    }
+
     fn run_function(&mut self) -> Result<Value, RuntimeError> {
         while !self.ast.is_empty() {
             let node = self.ast.pop_front().unwrap();
             match node {
-                Node::Assignment { name, value } => self.assign_variable(name, *value).unwrap(),
-                Node::IndexAssignment { name, index, value } => {
-                    self.assign_index(name, *index, *value).unwrap();
+                Node::Assignment { name, value, .. } => self.assign_variable(name, *value)?,
+                Node::IndexAssignment {
+                    target,
+                    index,
+                    value,
+                    ..
+                } => {
+                    self.assign_index(*target, *index, *value)?;
+                }
+                Node::Call { .. } => {
+                    self.call_function(node)?;
                 }
-                Node::Call { .. } => _ = self.call_function(node),
                 Node::If {
                     condition,
                     then_part,
                     else_part,
+                    ..
                 } => {
-                    let node = self.run_if(*condition, then_part, else_part).unwrap();
-                    if let Node::Return(value) = node {
+                    let node = self.run_if(*condition, then_part, else_part)?;
+                    if let Node::Return(value, _) = node {
                         return self.calculate(*value);
                     }
-                    if node == Node::Continue {
-                        continue;
-                    }
-                    if node == Node::Break {
-                        break;
+                    if matches!(node, Node::Break(_) | Node::Continue(_)) {
+                        return Err(RuntimeError::break_outside_loop(node, self.dictionary));
                     }
                 }
-                Node::Loop { body } => {
-                    let node = self.run_loop_inner_function(body).unwrap();
+                Node::Loop { body, .. } => {
+                    let node = self.run_loop_inner_function(body)?;
                     match node {
-                        Node::Return(value) => return self.calculate(*value),
-                        Node::Null => (),
+                        Node::Return(value, _) => return self.calculate(*value),
+                        Node::Null(_) => (),
                         _ => return Err(RuntimeError::unexpected_node(node)),
                     }
                 }
-                Node::Return(value) => return self.calculate(*value),
-                Node::Print(value) => {
-                    let value = self.calculate(*value).unwrap();
+                Node::Return(value, _) => return self.calculate(*value),
+                Node::Break(_) | Node::Continue(_) => {
+                    return Err(RuntimeError::break_outside_loop(node, self.dictionary));
+                }
+                Node::Print(value, _) => {
+                    let value = self.calculate(*value)?;
                     self.println(&value);
                 }
                 _ => return Err(RuntimeError::unexpected_node(node)),
@@ -106,39 +263,62 @@ impl Interpreter {
     }
 
     fn call_function(&mut self, value: Node) -> Result<Value, RuntimeError> {
-        let Node::Call { ref name, ref args } = value else {
+        let Node::Call {
+            ref name,
+            ref args,
+            ref span,
+        } = value
+        else {
             panic!()
         };
 
-        let Some(function) = self.functions.get(name) else {
-            return Err(RuntimeError::undefined_function(value));
+        let Some(function) = self.functions.get(&normalize(name)) else {
+            let suggestion = self.similar_function(name);
+            return Err(RuntimeError::undefined_function(
+                value,
+                suggestion.as_deref(),
+            ));
         };
 
-        let Node::Function {
-            name: _,
-            params,
-            body,
-        } = function.clone()
-        else {
+        let Node::Function { params, body, .. } = function.clone() else {
             panic!()
         };
 
         if params.len() != args.len() {
-            return Err(RuntimeError::wrong_number_of_arguments(value));
+            return Err(RuntimeError::wrong_number_of_arguments(value, params.len()));
+        }
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(RuntimeError::call_too_deep(
+                value,
+                MAX_CALL_DEPTH,
+                self.dictionary,
+            ));
         }
 
         let mut variables = HashMap::new();
+        let mut frame = Frame {
+            function: name.clone(),
+            args: Vec::new(),
+            span: span.clone(),
+        };
         for (param, arg) in params.iter().zip(args.iter()) {
-            variables.insert(param.clone(), self.calculate(arg.clone()).unwrap());
+            let value = self.calculate(arg.clone())?;
+            frame.args.push(value.describe());
+            variables.insert(normalize(param), (param.clone(), value));
         }
 
         let mut interpreter = Self {
             ast: Ast::new(body),
             functions: self.functions.clone(),
             variables,
+            interactive: false,
+            dictionary: self.dictionary,
+            depth: self.depth + 1,
         };
 
-        interpreter.run_function()
+        interpreter
+            .run_function()
+            .map_err(|error| error.with_frame(frame))
     }
 
     fn run_loop(&mut self, body: Vec<Node>) -> Result<(), RuntimeError> {
@@ -146,29 +326,41 @@ impl Interpreter {
         loop {
             let node = cycle.next().unwrap().clone();
             match node {
-                Node::Assignment { name, value } => _ = self.assign_variable(name, *value),
-                Node::IndexAssignment { name, index, value } => {
-                    self.assign_index(name, *index, *value).unwrap();
+                Node::Assignment { name, value, .. } => {
+                    self.assign_variable(name, *value)?;
+                }
+                Node::IndexAssignment {
+                    target,
+                    index,
+                    value,
+                    ..
+                } => {
+                    self.assign_index(*target, *index, *value)?;
+                }
+                Node::Call { .. } => {
+                    self.call_function(node)?;
                 }
-                Node::Call { .. } => _ = self.call_function(node),
                 Node::If {
                     condition,
                     then_part,
                     else_part,
+                    ..
                 } => {
-                    let node = self.run_if(*condition, then_part, else_part).unwrap();
-                    if node == Node::Continue {
+                    let node = self.run_if(*condition, then_part, else_part)?;
+                    if matches!(node, Node::Continue(_)) {
                         cycle = body.iter().cycle();
                     }
-                    if node == Node::Break {
+                    if matches!(node, Node::Break(_)) {
                         break;
                     }
                 }
-                Node::Loop { body } => _ = self.run_loop(body),
-                Node::Continue => continue,
-                Node::Break => break,
-                Node::Print(value) => {
-                    let value = self.calculate(*value).unwrap();
+                Node::Loop { body, .. } => {
+                    self.run_loop(body)?;
+                }
+                Node::Continue(_) => continue,
+                Node::Break(_) => break,
+                Node::Print(value, _) => {
+                    let value = self.calculate(*value)?;
                     self.println(&value);
                 }
                 _ => return Err(RuntimeError::unexpected_node(node)),
@@ -182,64 +374,55 @@ impl Interpreter {
         loop {
             let node = cycle.next().unwrap().clone();
             match node {
-                Node::Assignment { name, value } => _ = self.assign_variable(name, *value),
-                Node::IndexAssignment { name, index, value } => {
-                    self.assign_index(name, *index, *value).unwrap();
+                Node::Assignment { name, value, .. } => {
+                    self.assign_variable(name, *value)?;
+                }
+                Node::IndexAssignment {
+                    target,
+                    index,
+                    value,
+                    ..
+                } => {
+                    self.assign_index(*target, *index, *value)?;
+                }
+                Node::Call { .. } => {
+                    self.call_function(node)?;
                 }
-                Node::Call { .. } => _ = self.call_function(node),
                 Node::If {
                     condition,
                     then_part,
                     else_part,
+                    ..
                 } => {
-                    let node = self.run_if(*condition, then_part, else_part).unwrap();
-                    if let Node::Return(value) = node {
-                        return Ok(Node::Return(value));
+                    let node = self.run_if(*condition, then_part, else_part)?;
+                    if let Node::Return(..) = node {
+                        return Ok(node);
                     }
-                    if node == Node::Continue {
+                    if matches!(node, Node::Continue(_)) {
                         cycle = body.iter().cycle();
                     }
-                    if node == Node::Break {
+                    if matches!(node, Node::Break(_)) {
                         break;
                     }
                 }
-                Node::Loop { body } => _ = self.run_loop(body),
-                Node::Return(value) => return Ok(Node::Return(value)),
-                Node::Continue => continue,
-                Node::Break => break,
-                Node::Print(value) => {
-                    let value = self.calculate(*value).unwrap();
+                Node::Loop { body, .. } => {
+                    self.run_loop(body)?;
+                }
+                Node::Return(..) => return Ok(node),
+                Node::Continue(_) => continue,
+                Node::Break(_) => break,
+                Node::Print(value, _) => {
+                    let value = self.calculate(*value)?;
                     self.println(&value);
                 }
                 _ => return Err(RuntimeError::unexpected_node(node)),
             }
         }
-        Ok(Node::Null)
-    }
-
-    fn print(&self, value: &Value) {
-        match value {
-            Value::Number(number) => print!("{}", number),
-            Value::String(string) => print!("{}", string),
-            Value::Array { elements, length } => {
-                print!("[");
-                for (i, element) in elements.iter().enumerate() {
-                    self.print(element);
-                    if i < *length {
-                        print!(", ");
-                    }
-                }
-                print!("]");
-            }
-            Value::Bool(true) => print!("真"),
-            Value::Bool(false) => print!("偽"),
-            Value::Null => print!("無"),
-        }
+        Ok(Node::Null(Span::default()))
     }
 
     fn println(&self, value: &Value) {
-        self.print(value);
-        println!();
+        println!("{}", value);
     }
 
     fn run_if(
@@ -248,7 +431,7 @@ impl Interpreter {
         then_part: Vec<Node>,
         else_part: Vec<Node>,
     ) -> Result<Node, RuntimeError> {
-        let condition = self.calculate(condition).unwrap();
+        let condition = self.calculate(condition)?;
         if condition == Value::Bool(true) {
             self.run_if_children(then_part)
         } else {
@@ -261,115 +444,200 @@ impl Interpreter {
         while !body.is_empty() {
             let node = body.pop_front().unwrap();
             match node {
-                Node::Assignment { name, value } => _ = self.assign_variable(name, *value),
-                Node::Call { .. } => _ = self.call_function(node),
+                Node::Assignment { name, value, .. } => {
+                    self.assign_variable(name, *value)?;
+                }
+                Node::IndexAssignment {
+                    target,
+                    index,
+                    value,
+                    ..
+                } => {
+                    self.assign_index(*target, *index, *value)?;
+                }
+                Node::Call { .. } => {
+                    self.call_function(node)?;
+                }
                 Node::If {
                     condition,
                     then_part,
                     else_part,
+                    ..
                 } => {
-                    let node = self.run_if(*condition, then_part, else_part).unwrap();
+                    let node = self.run_if(*condition, then_part, else_part)?;
                     match node {
-                        Node::Continue | Node::Break => return Ok(node),
-                        Node::Null => (),
+                        Node::Continue(_) | Node::Break(_) => return Ok(node),
+                        Node::Null(_) => (),
                         _ => return Err(RuntimeError::unexpected_node(node)),
                     }
                 }
-                Node::Loop { body } => _ = self.run_loop(body),
-                Node::Return(value) => return Ok(Node::Return(value)),
-                Node::Continue => return Ok(Node::Continue),
-                Node::Break => return Ok(Node::Break),
-                Node::Print(value) => {
-                    let value = self.calculate(*value).unwrap();
+                Node::Loop { body, .. } => {
+                    self.run_loop(body)?;
+                }
+                Node::Return(..) => return Ok(node),
+                Node::Continue(_) | Node::Break(_) => return Ok(node),
+                Node::Print(value, _) => {
+                    let value = self.calculate(*value)?;
                     self.println(&value);
                 }
                 _ => return Err(RuntimeError::unexpected_node(node)),
             }
         }
-        Ok(Node::Null)
+        Ok(Node::Null(Span::default()))
     }
 
     fn assign_variable(&mut self, name: String, value: Node) -> Result<(), RuntimeError> {
-        let value = self.calculate(value);
-        self.variables.insert(name, value.unwrap());
+        let value = self.calculate(value)?;
+        self.variables.insert(normalize(&name), (name, value));
         Ok(())
     }
 
-    fn assign_index(&mut self, name: String, index: Node, value: Node) -> Result<(), RuntimeError> {
-        let index = self.calculate(index).unwrap();
-        let value = self.calculate(value).unwrap();
-        let array = self.variables.get_mut(&name).unwrap();
-        match array {
-            Value::Array { elements, length } => {
-                let index = match index {
-                    Value::Number(index) => index as usize,
-                    _ => panic!(),
-                };
-                if &index > length {
-                    return Err(RuntimeError::index_out_of_range(name.as_str(), index));
+    /// Assigns `value` to the element at `index` of `target`, which is a variable or
+    /// an element of one, such as `盤「１」` in `盤「１」「２」＝５`.
+    fn assign_index(&mut self, target: Node, index: Node, value: Node) -> Result<(), RuntimeError> {
+        // Each step is the array holding the element and the index into it, from the
+        // variable inward.
+        let mut steps = vec![(target, index)];
+        while let Some((Node::Index { target, index, .. }, _)) = steps.last() {
+            steps.push((*target.clone(), *index.clone()));
+        }
+        steps.reverse();
+        let mut indices = Vec::new();
+        for (_, index) in &steps {
+            indices.push(match self.calculate(index.clone())? {
+                Value::Number(number) => number,
+                other => {
+                    return Err(RuntimeError::invalid_index(
+                        index.clone(),
+                        other.type_name(),
+                    ))
                 }
-                elements[index] = value;
-            }
-            _ => return Err(RuntimeError::unexpected_node(Node::Variable(name))),
+            });
         }
+        let value = self.calculate(value)?;
+
+        let Some((Node::Variable(name, name_span), _)) = steps.first() else {
+            return Err(RuntimeError::unexpected_node(steps.remove(0).0));
+        };
+        let Some(mut element) = self
+            .variables
+            .get_mut(&normalize(name))
+            .map(|(_, value)| value)
+        else {
+            let suggestion = self.similar_variable(name);
+            let node = Node::Variable(name.clone(), name_span.clone());
+            return Err(RuntimeError::undefined_variable(
+                node,
+                suggestion.as_deref(),
+            ));
+        };
+        for ((target, index), number) in steps.iter().zip(indices) {
+            element = match element {
+                Value::Array { elements, length } => {
+                    &mut elements[element_index(number, *length, index)?]
+                }
+                other => return Err(RuntimeError::not_an_array(target, other.type_name())),
+            };
+        }
+        *element = value;
         Ok(())
     }
 
     fn calculate(&mut self, value: Node) -> Result<Value, RuntimeError> {
         Ok(match value {
-            Node::Number(number) => Value::Number(string_to_number(&number)),
-            Node::String(string) => Value::String(string),
-            Node::Array(length) => {
-                let len = self.calculate(*length.clone()).unwrap();
+            Node::Number(number, _) => Value::Number(string_to_number(&number)),
+            Node::String(string, _) => Value::String(string),
+            Node::Template(parts, _) => {
+                let mut string = String::new();
+                for part in parts {
+                    string += &self.calculate(part)?.to_string();
+                }
+                Value::String(string)
+            }
+            Node::Array(length, _) => {
+                let len = self.calculate(*length.clone())?;
                 let len = match len {
                     Value::Number(len) => len as usize,
-                    _ => return Err(RuntimeError::unexpected_node(*length)),
+                    other => {
+                        return Err(RuntimeError::invalid_array_length(
+                            *length,
+                            other.type_name(),
+                        ))
+                    }
                 };
                 Value::Array {
                     elements: vec![Value::Null; len + 1],
                     length: len,
                 }
             }
-            Node::Bool(b) => Value::Bool(b),
-            Node::Null => Value::Null,
-            Node::Variable(ref variable) => {
-                if let Some(value) = self.variables.get(variable) {
+            Node::Index {
+                ref target,
+                ref index,
+                ..
+            } => {
+                let array = self.calculate(*target.clone())?;
+                let number = match self.calculate(*index.clone())? {
+                    Value::Number(number) => number,
+                    other => {
+                        return Err(RuntimeError::invalid_index(
+                            *index.clone(),
+                            other.type_name(),
+                        ))
+                    }
+                };
+                match array {
+                    Value::Array {
+                        mut elements,
+                        length,
+                    } => elements.swap_remove(element_index(number, length, index)?),
+                    other => return Err(RuntimeError::not_an_array(target, other.type_name())),
+                }
+            }
+            Node::Bool(b, _) => Value::Bool(b),
+            Node::Null(_) => Value::Null,
+            Node::Variable(ref variable, _) => {
+                if let Some((_, value)) = self.variables.get(&normalize(variable)) {
                     value.clone()
                 } else {
-                    return Err(RuntimeError::undefined_variable(value));
+                    let suggestion = self.similar_variable(variable);
+                    return Err(RuntimeError::undefined_variable(
+                        value,
+                        suggestion.as_deref(),
+                    ));
                 }
             }
-            Node::Call { .. } => self.call_function(value).unwrap(),
+            Node::Call { .. } => self.call_function(value)?,
             Node::Or {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
-                match (left, right) {
-                    (Value::Bool(true), _) => Value::Bool(true),
-                    (_, Value::Bool(true)) => Value::Bool(true),
-                    _ => Value::Bool(false),
+                // The right side is not evaluated when the left side is already `真`.
+                if self.calculate_condition(&value, left)? {
+                    Value::Bool(true)
+                } else {
+                    Value::Bool(self.calculate_condition(&value, right)?)
                 }
             }
             Node::And {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
-                match (left, right) {
-                    (Value::Bool(false), _) => Value::Bool(false),
-                    (_, Value::Bool(false)) => Value::Bool(false),
-                    _ => Value::Bool(true),
+                // The right side is not evaluated when the left side is already `偽`.
+                if self.calculate_condition(&value, left)? {
+                    Value::Bool(self.calculate_condition(&value, right)?)
+                } else {
+                    Value::Bool(false)
                 }
             }
             Node::Equal {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
                 match (&left, &right) {
                     (Value::Number(left), Value::Number(right)) => {
                         if left == right {
@@ -420,9 +688,10 @@ impl Interpreter {
             Node::NotEqual {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
                 match (&left, &right) {
                     (Value::Number(left), Value::Number(right)) => {
                         if left != right {
@@ -473,10 +742,11 @@ impl Interpreter {
             Node::LessThan {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
-                match (left, right) {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (&left, &right) {
                     (Value::Number(left), Value::Number(right)) => {
                         if left < right {
                             Value::Bool(true)
@@ -484,16 +754,23 @@ impl Interpreter {
                             Value::Bool(false)
                         }
                     }
-                    _ => return Err(RuntimeError::comparing_different_types(value)),
+                    _ => {
+                        return Err(RuntimeError::comparing_different_types(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::LessThanOrEqual {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
-                match (left, right) {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (&left, &right) {
                     (Value::Number(left), Value::Number(right)) => {
                         if left <= right {
                             Value::Bool(true)
@@ -501,16 +778,23 @@ impl Interpreter {
                             Value::Bool(false)
                         }
                     }
-                    _ => return Err(RuntimeError::comparing_different_types(value)),
+                    _ => {
+                        return Err(RuntimeError::comparing_different_types(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::GreaterThan {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
-                match (left, right) {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (&left, &right) {
                     (Value::Number(left), Value::Number(right)) => {
                         if left > right {
                             Value::Bool(true)
@@ -518,16 +802,23 @@ impl Interpreter {
                             Value::Bool(false)
                         }
                     }
-                    _ => return Err(RuntimeError::comparing_different_types(value)),
+                    _ => {
+                        return Err(RuntimeError::comparing_different_types(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::GreaterThanOrEqual {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
-                match (left, right) {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (&left, &right) {
                     (Value::Number(left), Value::Number(right)) => {
                         if left >= right {
                             Value::Bool(true)
@@ -535,15 +826,22 @@ impl Interpreter {
                             Value::Bool(false)
                         }
                     }
-                    _ => return Err(RuntimeError::comparing_different_types(value)),
+                    _ => {
+                        return Err(RuntimeError::comparing_different_types(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::Add {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
                 match (left, right) {
                     (Value::Number(left), Value::Number(right)) => Value::Number(left + right),
                     (Value::String(left), Value::String(right)) => Value::String(left + &right),
@@ -553,75 +851,209 @@ impl Interpreter {
                     (Value::Number(left), Value::String(right)) => {
                         Value::String(left.to_string() + &right)
                     }
-                    _ => return Err(RuntimeError::string_addition(value)),
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_addition(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::Subtract {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
                 match (left, right) {
                     (Value::Number(left), Value::Number(right)) => Value::Number(left - right),
-                    _ => return Err(RuntimeError::string_addition(value)),
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_arithmetic(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::Multiply {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
                 match (left, right) {
                     (Value::Number(left), Value::Number(right)) => Value::Number(left * right),
-                    _ => return Err(RuntimeError::string_addition(value)),
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_arithmetic(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
             Node::Divide {
                 ref left,
                 ref right,
+                ..
             } => {
-                let left = self.calculate(*left.clone()).unwrap();
-                let right = self.calculate(*right.clone()).unwrap();
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
                 match (left, right) {
+                    (Value::Number(_), Value::Number(0.0)) => {
+                        return Err(RuntimeError::division_by_zero(value, self.dictionary))
+                    }
                     (Value::Number(left), Value::Number(right)) => Value::Number(left / right),
-                    _ => return Err(RuntimeError::string_addition(value)),
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_arithmetic(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
+                }
+            }
+            Node::FloorDivide {
+                ref left,
+                ref right,
+                ..
+            } => {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (left, right) {
+                    (Value::Number(_), Value::Number(0.0)) => {
+                        return Err(RuntimeError::division_by_zero(value, self.dictionary))
+                    }
+                    (Value::Number(left), Value::Number(right)) => {
+                        Value::Number((left / right).floor())
+                    }
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_arithmetic(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
+                }
+            }
+            Node::Modulo {
+                ref left,
+                ref right,
+                ..
+            } => {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (left, right) {
+                    (Value::Number(_), Value::Number(0.0)) => {
+                        return Err(RuntimeError::division_by_zero(value, self.dictionary))
+                    }
+                    // The remainder has the sign of the divisor, as in Python.
+                    (Value::Number(left), Value::Number(right)) => {
+                        Value::Number(left - right * (left / right).floor())
+                    }
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_arithmetic(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
+                }
+            }
+            Node::Power {
+                base: ref left,
+                exponent: ref right,
+                ..
+            } => {
+                let left = self.calculate(*left.clone())?;
+                let right = self.calculate(*right.clone())?;
+                match (left, right) {
+                    (Value::Number(left), Value::Number(right)) => Value::Number(left.powf(right)),
+                    (left, right) => {
+                        return Err(RuntimeError::invalid_arithmetic(
+                            value,
+                            left.type_name(),
+                            right.type_name(),
+                        ))
+                    }
                 }
             }
-            Node::Length(value) => {
-                let target = self.calculate(*value.clone()).unwrap();
+            Node::Length(value, _) => {
+                let target = self.calculate(*value.clone())?;
                 match target {
                     Value::String(string) => Value::Number(string.chars().count() as f64),
                     Value::Array { length, .. } => Value::Number(length as f64),
-                    _ => return Err(RuntimeError::has_no_length(*value)),
+                    other => return Err(RuntimeError::has_no_length(*value, other.type_name())),
                 }
             }
-            Node::Not(value) => {
-                let target = self.calculate(*value.clone()).unwrap();
+            Node::Not(value, _) => {
+                let target = self.calculate(*value.clone())?;
                 match target {
                     Value::Bool(b) => Value::Bool(!b),
-                    _ => return Err(RuntimeError::unexpected_node(*value)),
+                    other => return Err(RuntimeError::not_a_boolean(*value, other.type_name())),
                 }
             }
             _ => return Err(RuntimeError::unexpected_node(value)),
         })
     }
 
+    /// Evaluates an operand of `operator`, `かつ` or `または`, which must be a boolean.
+    fn calculate_condition(
+        &mut self,
+        operator: &Node,
+        operand: &Node,
+    ) -> Result<bool, RuntimeError> {
+        match self.calculate(operand.clone())? {
+            Value::Bool(b) => Ok(b),
+            other => Err(RuntimeError::non_boolean_operand(
+                operator,
+                operand.clone(),
+                other.type_name(),
+                self.dictionary,
+            )),
+        }
+    }
+
+    /// Returns the name of a variable, or of a value such as `真`, similar to `name`.
+    fn similar_variable(&self, name: &str) -> Option<String> {
+        let names = self.variables.values().map(|(name, _)| name.as_str());
+        suggest::similar(
+            name,
+            names.chain(Keyword::VALUES.map(|keyword| self.dictionary.spelling(&keyword))),
+        )
+        .map(String::from)
+    }
+
+    /// Returns the name of a function, or of a builtin such as `表示`, similar to `name`.
+    fn similar_function(&self, name: &str) -> Option<String> {
+        let names = self
+            .functions
+            .values()
+            .filter_map(|function| match function {
+                Node::Function { name, .. } => Some(name.as_str()),
+                _ => None,
+            });
+        suggest::similar(
+            name,
+            names.chain(Keyword::BUILTINS.map(|keyword| self.dictionary.spelling(&keyword))),
+        )
+        .map(String::from)
+    }
+
     fn drain_functions(&mut self) -> Result<(), RuntimeError> {
         let mut functions = VecDeque::from(self.ast.drain_functions());
         while !functions.is_empty() {
             let function = functions.pop_front().unwrap();
-            if let Node::Function {
-                ref name,
-                params: _,
-                body: _,
-            } = function
-            {
-                if self.functions.contains_key(name) {
+            if let Node::Function { ref name, .. } = function {
+                let key = normalize(name);
+                if !self.interactive && self.functions.contains_key(&key) {
                     return Err(RuntimeError::redefining_function(function));
                 }
-                self.functions.insert(name.clone(), function);
+                self.functions.insert(key, function);
             } else {
                 return Err(RuntimeError::unexpected_node(function));
             }
@@ -630,15 +1062,148 @@ impl Interpreter {
     }
 }
 
+/// Converts `number` into an index of an array of `length`. `index` is the expression
+/// it came from, pointed at when it is out of range.
+fn element_index(number: f64, length: usize, index: &Node) -> Result<usize, RuntimeError> {
+    if number < 0.0 || number as usize > length {
+        return Err(RuntimeError::index_out_of_range(
+            number,
+            length,
+            index.span().clone(),
+        ));
+    }
+    Ok(number as usize)
+}
+
+/// Number literals are validated and normalized into half-width by the scanner,
+/// so parsing them does not fail.
 fn string_to_number(string: &str) -> f64 {
-    string
-        .chars()
-        .map(|c| match c {
-            '０'..='９' => (c as u8 - '０' as u8 + b'0') as char,
-            '．' => '.',
-            _ => c,
-        })
-        .collect::<String>()
-        .parse()
-        .unwrap()
+    string.parse().unwrap_or(f64::NAN)
+}
+
+fn arguments_to_array(args: Vec<String>) -> Value {
+    // Arrays keep one more element than `length`, the same layout `配列` creates.
+    let length = args.len();
+    let mut elements: Vec<Value> = args.into_iter().map(Value::String).collect();
+    elements.push(Value::Null);
+    Value::Array { elements, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokens::{dictionary::STANDARD, Tokens};
+    use std::str::FromStr;
+
+    fn execute(source: &str) -> Result<Interpreter, RuntimeError> {
+        let tokens = Tokens::from_str(source).expect("the source scans");
+        let ast = Ast::try_from(tokens).expect("the source parses");
+        let mut interpreter = Interpreter::interactive(&STANDARD);
+        interpreter.execute(ast)?;
+        Ok(interpreter)
+    }
+
+    #[test]
+    fn assigns_nested_index_inside_if_block() {
+        let source = "盤＝配列（２）\n盤「１」＝配列（２）\nもし 真 なら\n    盤「１」「２」＝５\n";
+        let interpreter = execute(source).expect("the program runs");
+        let Some((_, Value::Array { elements, .. })) = interpreter.variables.get("盤") else {
+            panic!("盤 is an array");
+        };
+        let Value::Array { elements, .. } = &elements[1] else {
+            panic!("盤「１」 is an array");
+        };
+        assert_eq!(elements[2], Value::Number(5.0));
+    }
+
+    /// Evaluates `expression` by assigning it to a variable.
+    fn evaluate(expression: &str) -> Result<Value, RuntimeError> {
+        let mut interpreter = execute(&format!("結果 ＝ {}\n", expression))?;
+        let (_, value) = interpreter.variables.remove("結果").expect("assigned");
+        Ok(value)
+    }
+
+    #[test]
+    fn floors_quotients_toward_negative_infinity() {
+        assert_eq!(evaluate("７／／２").unwrap(), Value::Number(3.0));
+        assert_eq!(evaluate("ー７／／２").unwrap(), Value::Number(-4.0));
+        assert_eq!(evaluate("７／／ー２").unwrap(), Value::Number(-4.0));
+    }
+
+    #[test]
+    fn gives_remainders_the_sign_of_the_divisor() {
+        assert_eq!(evaluate("７％３").unwrap(), Value::Number(1.0));
+        assert_eq!(evaluate("ー７％３").unwrap(), Value::Number(2.0));
+        assert_eq!(evaluate("７％ー３").unwrap(), Value::Number(-2.0));
+    }
+
+    #[test]
+    fn groups_powers_to_the_right_and_before_unary_minus() {
+        assert_eq!(evaluate("２＾３＾２").unwrap(), Value::Number(512.0));
+        assert_eq!(evaluate("ー２＾２").unwrap(), Value::Number(-4.0));
+        assert_eq!(evaluate("ー２＾２ ＝＝ ー４").unwrap(), Value::Bool(true));
+        assert_eq!(evaluate("２＾ー１").unwrap(), Value::Number(0.5));
+    }
+
+    #[test]
+    fn reports_division_by_zero() {
+        for expression in ["１／０", "１／／０", "１％０", "１％（２ー２）"] {
+            let error = evaluate(expression).expect_err("dividing by zero fails");
+            assert_eq!(error.code, "R0017", "{}", expression);
+        }
+    }
+
+    #[test]
+    fn skips_the_right_operand_once_the_left_decides() {
+        assert_eq!(evaluate("偽 かつ 未定義（）").unwrap(), Value::Bool(false));
+        assert_eq!(evaluate("真 または 未定義（）").unwrap(), Value::Bool(true));
+        assert_eq!(evaluate("偽 かつ １").unwrap(), Value::Bool(false));
+        let error = evaluate("真 かつ 未定義（）").expect_err("the right operand runs");
+        assert_eq!(error.code, "R0006");
+
+        let source = "列 ＝ 配列（０）\nｘ ＝ ０\nもし 長さ（列）＞０ かつ 列「０」＝＝１ なら\n    ｘ ＝ １\n";
+        let interpreter = execute(source).expect("the index is not read");
+        assert_eq!(interpreter.variables["x"].1, Value::Number(0.0));
+    }
+
+    #[test]
+    fn rejects_non_boolean_operands() {
+        for expression in [
+            "１ かつ 真",
+            "真 かつ ”はい”",
+            "偽 または 無",
+            "配列（１） または 真",
+        ] {
+            let error = evaluate(expression).expect_err("the operand is not a boolean");
+            assert_eq!(error.code, "R0018", "{}", expression);
+        }
+    }
+
+    #[test]
+    fn reports_endless_recursion_with_every_call() {
+        let run = || {
+            let deep = "関数 和（ｎ）\n    もし ｎ ＝＝ ０ なら\n        かえす ０\n    かえす ｎ ＋ 和（ｎ ー １）\nｓ ＝ 和（９００）\n";
+            execute(deep).expect("recursion within the limit runs");
+            let endless = "関数 ｆ（ｎ）\n    かえす ｆ（ｎ）\nｆ（１）\n";
+            let error = execute(endless).expect_err("endless recursion fails");
+            (error.code, error.trace.backtrace.len())
+        };
+        let (code, frames) = std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(run)
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(code, "R0019");
+        assert_eq!(frames, MAX_CALL_DEPTH);
+    }
+
+    #[test]
+    fn suggests_variables_as_they_are_spelled() {
+        let interpreter = execute("ＡＢＣ ＝ １\n").expect("the program runs");
+        assert_eq!(
+            interpreter.similar_variable("ＡＢＤ").as_deref(),
+            Some("ＡＢＣ")
+        );
+    }
 }
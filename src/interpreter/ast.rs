@@ -13,18 +13,28 @@ pub struct Ast {
 }
 
 impl TryFrom<Tokens> for Ast {
-    type Error = SyntaxError;
+    type Error = Vec<SyntaxError>;
 
     fn try_from(tokens: Tokens) -> Result<Self, Self::Error> {
-        let parser = Parser::new(tokens);
-        let children = parser
-            .collect::<Result<VecDeque<Node>, SyntaxError>>()
-            .expect("構文エラー");
-        Ok(Self { children })
+        let (ast, errors) = Self::parse(tokens);
+        if errors.is_empty() {
+            Ok(ast)
+        } else {
+            Err(errors)
+        }
     }
 }
 
 impl Ast {
+    /// Parses the whole program, recovering from syntax errors.
+    /// Returns the statements that could be parsed along with every syntax error found.
+    pub fn parse(tokens: Tokens) -> (Self, Vec<SyntaxError>) {
+        let mut parser = Parser::new(tokens);
+        let children = parser.by_ref().collect::<VecDeque<Node>>();
+        let errors = parser.into_errors();
+        (Self { children }, errors)
+    }
+
     pub fn new(nodes: Vec<Node>) -> Self {
         Self {
             children: VecDeque::from(nodes),
@@ -35,6 +45,10 @@ impl Ast {
         self.children.is_empty()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Node> {
+        self.children.iter()
+    }
+
     pub fn pop_front(&mut self) -> Option<Node> {
         self.children.pop_front()
     }
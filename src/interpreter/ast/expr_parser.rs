@@ -1,7 +1,13 @@
 use super::node::Node;
 use crate::interpreter::{
-    error::SyntaxError,
-    tokens::{keyword::Keyword, lexeme::Lexeme, symbol::Symbol, Tokens},
+    error::{SyntaxError, SyntaxErrorKind},
+    span::Span,
+    tokens::{
+        keyword::Keyword,
+        lexeme::{Lexeme, TemplatePart},
+        symbol::Symbol,
+        Tokens,
+    },
 };
 
 pub fn parse_expression(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
@@ -9,13 +15,16 @@ pub fn parse_expression(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_or(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let mut node = parse_and(tokens).unwrap();
+    let mut node = parse_and(tokens)?;
 
     loop {
         if tokens.consume(Lexeme::Keyword(Keyword::Or)).is_ok() {
+            let right = parse_and(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::Or {
                 left: Box::new(node),
-                right: Box::new(parse_and(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else {
             return Ok(node);
@@ -24,13 +33,16 @@ fn parse_or(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_and(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let mut node = parse_equality(tokens).unwrap();
+    let mut node = parse_equality(tokens)?;
 
     loop {
         if tokens.consume(Lexeme::Keyword(Keyword::And)).is_ok() {
+            let right = parse_equality(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::And {
                 left: Box::new(node),
-                right: Box::new(parse_equality(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else {
             return Ok(node);
@@ -39,18 +51,24 @@ fn parse_and(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_equality(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let mut node = parse_relational(tokens).unwrap();
+    let mut node = parse_relational(tokens)?;
 
     loop {
         if tokens.consume(Lexeme::Symbol(Symbol::EqualEqual)).is_ok() {
+            let right = parse_relational(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::Equal {
                 left: Box::new(node),
-                right: Box::new(parse_relational(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else if tokens.consume(Lexeme::Symbol(Symbol::BangEqual)).is_ok() {
+            let right = parse_relational(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::NotEqual {
                 left: Box::new(node),
-                right: Box::new(parse_relational(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else {
             return Ok(node);
@@ -59,28 +77,40 @@ fn parse_equality(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_relational(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let mut node = parse_add_or_sub(tokens).unwrap();
+    let mut node = parse_add_or_sub(tokens)?;
 
     loop {
         if tokens.consume(Lexeme::Symbol(Symbol::Less)).is_ok() {
+            let right = parse_add_or_sub(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::LessThan {
                 left: Box::new(node),
-                right: Box::new(parse_add_or_sub(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else if tokens.consume(Lexeme::Symbol(Symbol::LessEqual)).is_ok() {
+            let right = parse_add_or_sub(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::LessThanOrEqual {
                 left: Box::new(node),
-                right: Box::new(parse_add_or_sub(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else if tokens.consume(Lexeme::Symbol(Symbol::Greater)).is_ok() {
+            let right = parse_add_or_sub(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::GreaterThan {
                 left: Box::new(node),
-                right: Box::new(parse_add_or_sub(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else if tokens.consume(Lexeme::Symbol(Symbol::GreaterEqual)).is_ok() {
+            let right = parse_add_or_sub(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::GreaterThanOrEqual {
                 left: Box::new(node),
-                right: Box::new(parse_add_or_sub(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else {
             return Ok(node);
@@ -89,18 +119,24 @@ fn parse_relational(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_add_or_sub(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let mut node = parse_mul_or_div(tokens).unwrap();
+    let mut node = parse_mul_or_div(tokens)?;
 
     loop {
         if tokens.consume(Lexeme::Symbol(Symbol::Plus)).is_ok() {
+            let right = parse_mul_or_div(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::Add {
                 left: Box::new(node),
-                right: Box::new(parse_mul_or_div(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else if tokens.consume(Lexeme::Symbol(Symbol::Minus)).is_ok() {
+            let right = parse_mul_or_div(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::Subtract {
                 left: Box::new(node),
-                right: Box::new(parse_mul_or_div(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else {
             return Ok(node);
@@ -109,18 +145,40 @@ fn parse_add_or_sub(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_mul_or_div(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let mut node = parse_unary(tokens).unwrap();
+    let mut node = parse_unary(tokens)?;
 
     loop {
         if tokens.consume(Lexeme::Symbol(Symbol::Asterisk)).is_ok() {
+            let right = parse_unary(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::Multiply {
                 left: Box::new(node),
-                right: Box::new(parse_unary(tokens).unwrap()),
+                right: Box::new(right),
+                span,
             };
         } else if tokens.consume(Lexeme::Symbol(Symbol::Slash)).is_ok() {
+            let right = parse_unary(tokens)?;
+            let span = node.span().to(right.span());
             node = Node::Divide {
                 left: Box::new(node),
-                right: Box::new(parse_unary(tokens).unwrap()),
+                right: Box::new(right),
+                span,
+            };
+        } else if tokens.consume(Lexeme::Symbol(Symbol::SlashSlash)).is_ok() {
+            let right = parse_unary(tokens)?;
+            let span = node.span().to(right.span());
+            node = Node::FloorDivide {
+                left: Box::new(node),
+                right: Box::new(right),
+                span,
+            };
+        } else if tokens.consume(Lexeme::Symbol(Symbol::Percent)).is_ok() {
+            let right = parse_unary(tokens)?;
+            let span = node.span().to(right.span());
+            node = Node::Modulo {
+                left: Box::new(node),
+                right: Box::new(right),
+                span,
             };
         } else {
             return Ok(node);
@@ -129,30 +187,66 @@ fn parse_mul_or_div(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
 }
 
 fn parse_unary(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
+    let start = tokens.next_span();
     if tokens.consume(Lexeme::Symbol(Symbol::Minus)).is_ok() {
+        let right = parse_power(tokens)?;
         Ok(Node::Subtract {
-            left: Box::new(Node::Number("0".to_string())),
-            right: Box::new(parse_primary(tokens).unwrap()),
+            left: Box::new(Node::Number("0".to_string(), start.clone())),
+            span: start.to(right.span()),
+            right: Box::new(right),
         })
     } else if tokens.consume(Lexeme::Symbol(Symbol::Bang)).is_ok() {
-        Ok(Node::Not(Box::new(parse_primary(tokens).unwrap())))
+        let value = parse_power(tokens)?;
+        let span = start.to(value.span());
+        Ok(Node::Not(Box::new(value), span))
+    } else {
+        parse_power(tokens)
+    }
+}
+
+/// `＾` binds tighter than a unary `ー` on its left, so `ー２＾２` is `ー（２＾２）`,
+/// and groups to the right, so `２＾３＾２` is `２＾（３＾２）`.
+fn parse_power(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
+    let base = parse_primary(tokens)?;
+    if tokens.consume(Lexeme::Symbol(Symbol::Caret)).is_ok() {
+        let exponent = parse_unary(tokens)?;
+        let span = base.span().to(exponent.span());
+        Ok(Node::Power {
+            base: Box::new(base),
+            exponent: Box::new(exponent),
+            span,
+        })
     } else {
-        parse_primary(tokens)
+        Ok(base)
     }
 }
 
+/// Parses a value followed by any number of indexes, such as `盤「１」「２」`.
 fn parse_primary(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    if tokens.consume(Lexeme::Symbol(Symbol::OpenParen)).is_ok() {
-        let node = parse_expression(tokens).unwrap();
-        tokens.consume(Lexeme::Symbol(Symbol::CloseParen)).unwrap();
-        Ok(node)
+    let start = tokens.next_span();
+    let mut node = if tokens.consume(Lexeme::Symbol(Symbol::OpenParen)).is_ok() {
+        let node = parse_expression(tokens)?;
+        tokens.expect(Lexeme::Symbol(Symbol::CloseParen))?;
+        node
     } else {
-        parse_terminal(tokens)
+        parse_terminal(tokens)?
+    };
+
+    while tokens.consume(Lexeme::Symbol(Symbol::OpenBracket)).is_ok() {
+        let index = parse_expression(tokens)?;
+        tokens.expect(Lexeme::Symbol(Symbol::CloseBracket))?;
+        node = Node::Index {
+            span: tokens.span_from(&start),
+            target: Box::new(node),
+            index: Box::new(index),
+        };
     }
+    Ok(node)
 }
 
 fn parse_terminal(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let token = tokens.shift().unwrap();
+    let token = tokens.next_token()?;
+    let start = token.span.clone();
     match token.lexeme {
         Lexeme::Identifier(name) => {
             // Function call
@@ -163,42 +257,72 @@ fn parse_terminal(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
                         break;
                     }
                     if tokens.consume(Lexeme::Symbol(Symbol::Comma)).is_err() {
-                        args.push(parse_expression(tokens).unwrap());
+                        args.push(parse_expression(tokens)?);
                     }
                 }
-                Ok(Node::Call { name, args })
-            // Array index
-            } else if tokens.consume(Lexeme::Symbol(Symbol::OpenBracket)).is_ok() {
-                let index = parse_expression(tokens).unwrap();
-                tokens
-                    .consume(Lexeme::Symbol(Symbol::CloseBracket))
-                    .unwrap();
-                Ok(Node::Index {
+                Ok(Node::Call {
                     name,
-                    index: Box::new(index),
+                    args,
+                    span: tokens.span_from(&start),
                 })
             // Variable
             } else {
-                Ok(Node::Variable(name))
+                Ok(Node::Variable(name, start))
             }
         }
-        Lexeme::Number(number) => Ok(Node::Number(number)),
-        Lexeme::String(string) => Ok(Node::String(string)),
-        Lexeme::Keyword(Keyword::Null) => Ok(Node::Null),
-        Lexeme::Keyword(Keyword::True) => Ok(Node::Bool(true)),
-        Lexeme::Keyword(Keyword::False) => Ok(Node::Bool(false)),
+        Lexeme::Number(number) => Ok(Node::Number(number, start)),
+        Lexeme::String(string) => Ok(Node::String(string, start)),
+        Lexeme::Template(parts) => parse_template(parts, start, tokens),
+        Lexeme::Keyword(Keyword::Null) => Ok(Node::Null(start)),
+        Lexeme::Keyword(Keyword::True) => Ok(Node::Bool(true, start)),
+        Lexeme::Keyword(Keyword::False) => Ok(Node::Bool(false, start)),
         Lexeme::Keyword(Keyword::Array) => {
-            tokens.consume(Lexeme::Symbol(Symbol::OpenParen)).unwrap();
-            let length = parse_expression(tokens).unwrap();
-            tokens.consume(Lexeme::Symbol(Symbol::CloseParen)).unwrap();
-            Ok(Node::Array(Box::new(length)))
+            tokens.expect(Lexeme::Symbol(Symbol::OpenParen))?;
+            let length = parse_expression(tokens)?;
+            tokens.expect(Lexeme::Symbol(Symbol::CloseParen))?;
+            Ok(Node::Array(Box::new(length), tokens.span_from(&start)))
         }
         Lexeme::Keyword(Keyword::Length) => {
-            tokens.consume(Lexeme::Symbol(Symbol::OpenParen)).unwrap();
-            let list = parse_expression(tokens).unwrap();
-            tokens.consume(Lexeme::Symbol(Symbol::CloseParen)).unwrap();
-            Ok(Node::Length(Box::new(list)))
+            tokens.expect(Lexeme::Symbol(Symbol::OpenParen))?;
+            let list = parse_expression(tokens)?;
+            tokens.expect(Lexeme::Symbol(Symbol::CloseParen))?;
+            Ok(Node::Length(Box::new(list), tokens.span_from(&start)))
+        }
+        _ => {
+            let text = tokens.text(&token);
+            Err(SyntaxError::unexpected_token(token, text))
+        }
+    }
+}
+
+/// Parses each expression embedded in a string literal read from `outer`.
+fn parse_template(
+    parts: Vec<TemplatePart>,
+    span: Span,
+    outer: &Tokens,
+) -> Result<Node, SyntaxError> {
+    let mut nodes = Vec::new();
+    for part in parts {
+        match part {
+            TemplatePart::Text(text) => nodes.push(Node::String(text, span.clone())),
+            TemplatePart::Expression(tokens, span) => {
+                let mut tokens = Tokens::embedded(tokens, span.clone(), outer);
+                // An expression cut short by the `｝` is not the end of the program.
+                let node = parse_expression(&mut tokens).map_err(|error| {
+                    if error.kind() == SyntaxErrorKind::UnexpectedEnd {
+                        let detail = "「｝」の前で式が終わっていません";
+                        SyntaxError::malformed_interpolation(detail, span)
+                    } else {
+                        error
+                    }
+                })?;
+                nodes.push(node);
+                if let Some(token) = tokens.shift() {
+                    let text = tokens.text(&token);
+                    return Err(SyntaxError::unexpected_token(token, text));
+                }
+            }
         }
-        _ => Err(SyntaxError::unexpected_token(token)),
     }
+    Ok(Node::Template(nodes, span))
 }
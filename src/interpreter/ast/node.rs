@@ -1,105 +1,191 @@
+use crate::interpreter::span::Span;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Node {
-    Number(String),
-    String(String),
-    Array(Box<Node>),
-    Bool(bool),
-    Null,
-    Not(Box<Node>),
+    Number(String, Span),
+    String(String, Span),
+    /// syntax is `”` (text) `｛` (value) `｝` (text) `”`
+    Template(Vec<Node>, Span),
+    Array(Box<Node>, Span),
+    Bool(bool, Span),
+    Null(Span),
+    Not(Box<Node>, Span),
     Assignment {
         name: String,
         value: Box<Node>,
+        span: Span,
     },
-    /// syntax is (name) `「` (index) `」` `＝` (value)
+    /// syntax is (target) `「` (index) `」` `＝` (value), where the target is a
+    /// variable or another index such as `盤「１」`.
     IndexAssignment {
-        name: String,
+        target: Box<Node>,
         index: Box<Node>,
         value: Box<Node>,
+        span: Span,
     },
-    Variable(String),
-    /// syntax is (name) `「` (index) `」`
+    Variable(String, Span),
+    /// syntax is (target) `「` (index) `」`
     Index {
-        name: String,
+        target: Box<Node>,
         index: Box<Node>,
+        span: Span,
     },
     /// syntax is `関数` (name) `（` (args) `）` (body)
     Function {
         name: String,
         params: Vec<String>,
         body: Vec<Node>,
+        /// The `＃＃` comments above the definition, joined with line breaks.
+        doc: Option<String>,
+        span: Span,
     },
     Call {
         name: String,
         args: Vec<Node>,
+        span: Span,
     },
     /// syntax is (left) `または` (right)
     Or {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     /// syntax is (left) `かつ` (right)
     And {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     Equal {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     NotEqual {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     LessThan {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     LessThanOrEqual {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     GreaterThan {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     GreaterThanOrEqual {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     Add {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     Subtract {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     Multiply {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
     },
     Divide {
         left: Box<Node>,
         right: Box<Node>,
+        span: Span,
+    },
+    /// syntax is (left) `／／` (right)
+    FloorDivide {
+        left: Box<Node>,
+        right: Box<Node>,
+        span: Span,
+    },
+    /// syntax is (left) `％` (right)
+    Modulo {
+        left: Box<Node>,
+        right: Box<Node>,
+        span: Span,
+    },
+    /// syntax is (base) `＾` (exponent)
+    Power {
+        base: Box<Node>,
+        exponent: Box<Node>,
+        span: Span,
     },
     /// syntax is `もし` (condition) `なら` (then_part) `ちがえば` (else_part)
     If {
         condition: Box<Node>,
         then_part: Vec<Node>,
         else_part: Vec<Node>,
+        span: Span,
     },
     /// syntax is `くりかえし` (body)
     Loop {
         body: Vec<Node>,
+        span: Span,
     },
     /// syntax is `もどす` (value)
-    Return(Box<Node>),
+    Return(Box<Node>, Span),
     /// syntax is `ぬける`
-    Break,
+    Break(Span),
     /// syntax is `つぎへ`
-    Continue,
+    Continue(Span),
     /// syntax is `表示（` (value) `）`
-    Print(Box<Node>),
+    Print(Box<Node>, Span),
     /// syntax is `長さ（` (value) `）`
-    Length(Box<Node>),
+    Length(Box<Node>, Span),
+}
+
+impl Node {
+    pub fn span(&self) -> &Span {
+        match self {
+            Node::Number(_, span) => span,
+            Node::String(_, span) => span,
+            Node::Template(_, span) => span,
+            Node::Array(_, span) => span,
+            Node::Bool(_, span) => span,
+            Node::Null(span) => span,
+            Node::Not(_, span) => span,
+            Node::Assignment { span, .. } => span,
+            Node::IndexAssignment { span, .. } => span,
+            Node::Variable(_, span) => span,
+            Node::Index { span, .. } => span,
+            Node::Function { span, .. } => span,
+            Node::Call { span, .. } => span,
+            Node::Or { span, .. } => span,
+            Node::And { span, .. } => span,
+            Node::Equal { span, .. } => span,
+            Node::NotEqual { span, .. } => span,
+            Node::LessThan { span, .. } => span,
+            Node::LessThanOrEqual { span, .. } => span,
+            Node::GreaterThan { span, .. } => span,
+            Node::GreaterThanOrEqual { span, .. } => span,
+            Node::Add { span, .. } => span,
+            Node::Subtract { span, .. } => span,
+            Node::Multiply { span, .. } => span,
+            Node::Divide { span, .. } => span,
+            Node::FloorDivide { span, .. } => span,
+            Node::Modulo { span, .. } => span,
+            Node::Power { span, .. } => span,
+            Node::If { span, .. } => span,
+            Node::Loop { span, .. } => span,
+            Node::Return(_, span) => span,
+            Node::Break(span) => span,
+            Node::Continue(span) => span,
+            Node::Print(_, span) => span,
+            Node::Length(_, span) => span,
+        }
+    }
 }
@@ -1,86 +1,223 @@
 use super::node::Node;
 use crate::interpreter::{
     ast::expr_parser::parse_expression,
-    error::SyntaxError,
-    tokens::{keyword::Keyword, lexeme::Lexeme, symbol::Symbol, Tokens},
+    error::{Suggestion, SyntaxError},
+    span::Span,
+    suggest,
+    tokens::{
+        dictionary::Dictionary, keyword::Keyword, lexeme::Lexeme, symbol::Symbol, token::Token,
+        Tokens,
+    },
 };
 
+/// Parses statements one by one. When a statement has a syntax error, the error is
+/// recorded, the parser skips to the next statement, and the statement is left out.
 #[derive(Debug)]
 pub struct Parser {
     tokens: Tokens,
+    errors: Vec<SyntaxError>,
+    /// The errors found while scanning, each with an invalid token in its place.
+    scan_errors: Vec<SyntaxError>,
+    /// The spans of the invalid tokens, which are already reported.
+    invalid_spans: Vec<Span>,
 }
 
 impl Iterator for Parser {
-    type Item = Result<Node, SyntaxError>;
+    type Item = Node;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.tokens.front().is_none() {
-            return None;
-        };
-        Some(parse_node(&mut self.tokens))
+        while self.tokens.front().is_some() {
+            match parse_node(&mut self.tokens, &mut self.errors) {
+                Ok(node) => return Some(node),
+                Err(error) => {
+                    self.errors.push(error);
+                    synchronize(&mut self.tokens);
+                }
+            }
+        }
+        None
     }
 }
 
 impl Parser {
-    pub fn new(tokens: Tokens) -> Self {
-        Self { tokens }
+    /// Every error found while scanning is reported, even when a statement has several
+    /// of them.
+    pub fn new(mut tokens: Tokens) -> Self {
+        let invalid_spans = (0..tokens.len())
+            .filter_map(|index| match tokens.get(index) {
+                Some(Token {
+                    lexeme: Lexeme::Invalid(_),
+                    span,
+                }) => Some(span.clone()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            scan_errors: tokens.take_errors(),
+            tokens,
+            errors: Vec::new(),
+            invalid_spans,
+        }
     }
-}
 
-fn parse_node(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let front = tokens.front().unwrap();
+    /// Returns the syntax errors found, in the order they appear in the source.
+    pub fn into_errors(mut self) -> Vec<SyntaxError> {
+        // An invalid token is not reported again where the parser stops at it.
+        let invalid_spans = self.invalid_spans;
+        self.errors.retain(|error| {
+            error
+                .span
+                .as_ref()
+                .is_none_or(|span| !invalid_spans.contains(span))
+        });
+        self.errors.append(&mut self.scan_errors);
+        self.errors.sort_by_key(|error| {
+            error
+                .span
+                .as_ref()
+                .map_or(usize::MAX, |span| span.start.char)
+        });
+        self.errors
+    }
+}
 
-    let node = match &front.lexeme {
-        Lexeme::Keyword(keyword) => match keyword {
-            Keyword::If => parse_if(tokens),
-            Keyword::Function => parse_function(tokens),
-            Keyword::Return => parse_return(tokens),
-            Keyword::Loop => parse_loop(tokens),
-            Keyword::Continue => parse_continue(tokens),
-            Keyword::Break => parse_break(tokens),
-            Keyword::Print => parse_print(tokens),
-            _ => Err(SyntaxError::unexpected_token(front.clone())),
-        },
-        Lexeme::Identifier(_) => {
-            if is_assignment(tokens) {
-                parse_assignment(tokens)
-            } else {
-                parse_expression(tokens)
+/// Skips the rest of the current statement, including the block it opens, if any.
+/// Stops before a `Dedent` that closes the enclosing block.
+fn synchronize(tokens: &mut Tokens) {
+    let mut depth = 0;
+    while let Some(token) = tokens.front() {
+        match token.lexeme {
+            Lexeme::Newline if depth == 0 => {
+                _ = tokens.shift();
+                if tokens.front().map(|token| &token.lexeme) != Some(&Lexeme::Indent) {
+                    return;
+                }
+                continue;
             }
+            Lexeme::Indent => depth += 1,
+            Lexeme::Dedent if depth == 0 => return,
+            Lexeme::Dedent => {
+                depth -= 1;
+                if depth == 0 {
+                    // The skipped block has ended, and the next statement follows.
+                    _ = tokens.shift();
+                    return;
+                }
+            }
+            _ => (),
         }
-        _ => Err(SyntaxError::unexpected_token(front.clone())),
+        _ = tokens.shift();
+    }
+}
+
+fn parse_node(tokens: &mut Tokens, errors: &mut Vec<SyntaxError>) -> Result<Node, SyntaxError> {
+    let Some(front) = tokens.front() else {
+        return Err(SyntaxError::unexpected_end_of_file(tokens.next_span()));
     };
 
-    tokens.consume(Lexeme::Newline).ok();
-    node
+    match &front.lexeme {
+        Lexeme::DocComment(doc) => {
+            let doc = doc.clone();
+            _ = tokens.shift();
+            match tokens.front() {
+                Some(token) if token.lexeme == Lexeme::Keyword(Keyword::Function) => {
+                    parse_function(tokens, errors, Some(doc))
+                }
+                _ => parse_node(tokens, errors),
+            }
+        }
+        Lexeme::Keyword(keyword) => match keyword {
+            Keyword::If => parse_if(tokens, errors),
+            Keyword::Function => parse_function(tokens, errors, None),
+            Keyword::Return => parse_line(tokens, parse_return),
+            Keyword::Loop => parse_loop(tokens, errors),
+            Keyword::Continue => parse_line(tokens, parse_continue),
+            Keyword::Break => parse_line(tokens, parse_break),
+            Keyword::Print => parse_line(tokens, parse_print),
+            _ => parse_line(tokens, parse_expression),
+        },
+        Lexeme::Identifier(_) => parse_identifier_statement(tokens),
+        _ => parse_line(tokens, parse_expression),
+    }
 }
 
-fn parse_node_in_block(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let front = tokens.front().unwrap();
+fn parse_node_in_block(
+    tokens: &mut Tokens,
+    errors: &mut Vec<SyntaxError>,
+) -> Result<Node, SyntaxError> {
+    let Some(front) = tokens.front() else {
+        return Err(SyntaxError::unexpected_end_of_file(tokens.next_span()));
+    };
 
-    let node = match &front.lexeme {
+    match &front.lexeme {
+        Lexeme::DocComment(_) => {
+            _ = tokens.shift();
+            parse_node_in_block(tokens, errors)
+        }
         Lexeme::Keyword(keyword) => match keyword {
-            Keyword::If => parse_if(tokens),
+            Keyword::If => parse_if(tokens, errors),
             Keyword::Function => Err(SyntaxError::function_declaration_in_block(front.clone())),
-            Keyword::Return => parse_return(tokens),
-            Keyword::Loop => parse_loop(tokens),
-            Keyword::Continue => parse_continue(tokens),
-            Keyword::Break => parse_break(tokens),
-            Keyword::Print => parse_print(tokens),
-            _ => Err(SyntaxError::unexpected_token(front.clone())),
+            Keyword::Return => parse_line(tokens, parse_return),
+            Keyword::Loop => parse_loop(tokens, errors),
+            Keyword::Continue => parse_line(tokens, parse_continue),
+            Keyword::Break => parse_line(tokens, parse_break),
+            Keyword::Print => parse_line(tokens, parse_print),
+            _ => Err(SyntaxError::unexpected_token(
+                front.clone(),
+                tokens.text(front),
+            )),
         },
-        Lexeme::Identifier(_) => {
-            if is_assignment(tokens) {
-                parse_assignment(tokens)
-            } else {
-                parse_expression(tokens)
-            }
-        }
-        _ => Err(SyntaxError::unexpected_token(front.clone())),
+        Lexeme::Identifier(_) => parse_identifier_statement(tokens),
+        _ => Err(SyntaxError::unexpected_token(
+            front.clone(),
+            tokens.text(front),
+        )),
+    }
+}
+
+/// Parses a statement starting with an identifier, which may be a misspelled keyword
+/// such as `もしく` or `くりかえす`.
+fn parse_identifier_statement(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
+    let front = tokens.front().cloned();
+    let keyword = match &front {
+        Some(Token {
+            lexeme: Lexeme::Identifier(name),
+            ..
+        }) => similar_keyword(name, tokens.dictionary()),
+        _ => None,
     };
 
-    tokens.consume(Lexeme::Newline).ok();
-    node
+    let result = if is_assignment(tokens) {
+        parse_line(tokens, parse_assignment)
+    } else {
+        parse_line(tokens, parse_expression)
+    };
+    match (result, keyword, front) {
+        (Ok(Node::Variable(..)), Some(keyword), Some(front)) => Err(
+            SyntaxError::misspelled_keyword(front, &keyword, tokens.dictionary()),
+        ),
+        (Err(error), Some(keyword), Some(front)) => Err(error.with_suggestion(
+            Suggestion::did_you_mean(front.span, tokens.dictionary().spelling(&keyword)),
+        )),
+        (result, _, _) => result,
+    }
+}
+
+/// Finds the keyword in `dictionary` that `name` looks like a misspelling of.
+fn similar_keyword(name: &str, dictionary: &Dictionary) -> Option<Keyword> {
+    let spellings = Keyword::ALL.map(|keyword| dictionary.spelling(&keyword));
+    let spelling = suggest::similar(name, spellings)?;
+    dictionary.keyword(spelling)
+}
+
+/// Parses a statement that does not open a block, which must end with a newline.
+fn parse_line(
+    tokens: &mut Tokens,
+    parse: fn(&mut Tokens) -> Result<Node, SyntaxError>,
+) -> Result<Node, SyntaxError> {
+    let node = parse(tokens)?;
+    tokens.expect(Lexeme::Newline)?;
+    Ok(node)
 }
 
 fn is_assignment(tokens: &Tokens) -> bool {
@@ -94,133 +231,240 @@ fn is_assignment(tokens: &Tokens) -> bool {
     false
 }
 
-fn parse_block(tokens: &mut Tokens) -> Result<Vec<Node>, SyntaxError> {
-    tokens.consume(Lexeme::Indent).unwrap();
+fn parse_block(
+    tokens: &mut Tokens,
+    errors: &mut Vec<SyntaxError>,
+) -> Result<Vec<Node>, SyntaxError> {
+    tokens.expect(Lexeme::Indent)?;
 
     let mut body = Vec::new();
     while tokens.consume(Lexeme::Dedent).is_err() {
-        body.push(parse_node_in_block(tokens).unwrap());
+        match parse_node_in_block(tokens, errors) {
+            Ok(node) => body.push(node),
+            Err(error) => {
+                let end_of_file = tokens.front().is_none();
+                errors.push(error);
+                if end_of_file {
+                    break;
+                }
+                synchronize(tokens);
+            }
+        }
     }
     Ok(body)
 }
 
+/// Parses an assignment to a place, which is a variable or an index of one such as
+/// `盤「１」「２」`.
 pub fn parse_assignment(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    let front = tokens.shift().unwrap();
-    if let Lexeme::Identifier(name) = front.lexeme {
-        // Assign a value to an index of an array.
-        if tokens.consume(Lexeme::Symbol(Symbol::OpenBracket)).is_ok() {
-            let index = Box::new(parse_expression(tokens).unwrap());
-            tokens
-                .consume(Lexeme::Symbol(Symbol::CloseBracket))
-                .unwrap();
-            tokens.consume(Lexeme::Symbol(Symbol::Equal)).unwrap();
-            let value = Box::new(parse_expression(tokens).unwrap());
-            return Ok(Node::IndexAssignment { name, index, value });
-        }
-        // Assign a value to a variable.
-        tokens.consume(Lexeme::Symbol(Symbol::Equal)).unwrap();
-        let value = Box::new(parse_expression(tokens).unwrap());
-        Ok(Node::Assignment { name, value })
-    } else {
-        Err(SyntaxError::unexpected_token(front))
+    let start = tokens.next_span();
+    let place = parse_expression(tokens)?;
+    if !is_place(&place) {
+        return Err(SyntaxError::invalid_assignment_target(place.span().clone()));
+    }
+    tokens.expect(Lexeme::Symbol(Symbol::Equal))?;
+    let value = Box::new(parse_expression(tokens)?);
+    let span = tokens.span_from(&start);
+    Ok(match place {
+        Node::Index { target, index, .. } => Node::IndexAssignment {
+            target,
+            index,
+            value,
+            span,
+        },
+        Node::Variable(name, _) => Node::Assignment { name, value, span },
+        _ => unreachable!(),
+    })
+}
+
+fn is_place(node: &Node) -> bool {
+    match node {
+        Node::Variable(..) => true,
+        Node::Index { target, .. } => is_place(target),
+        _ => false,
     }
 }
 
-fn parse_function(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    tokens.consume(Lexeme::Keyword(Keyword::Function)).unwrap();
+/// Parses a function definition. `doc` is the documentation comment above it.
+fn parse_function(
+    tokens: &mut Tokens,
+    errors: &mut Vec<SyntaxError>,
+    doc: Option<String>,
+) -> Result<Node, SyntaxError> {
+    let start = tokens.next_span();
+    tokens.expect(Lexeme::Keyword(Keyword::Function))?;
 
-    let token = tokens.shift().unwrap();
+    let token = tokens.next_token()?;
     let Lexeme::Identifier(name) = token.lexeme else {
-        return Err(SyntaxError::unexpected_token(token));
+        let text = tokens.text(&token);
+        return Err(SyntaxError::unexpected_token(token, text));
     };
 
-    tokens.consume(Lexeme::Symbol(Symbol::OpenParen)).unwrap();
+    tokens.expect(Lexeme::Symbol(Symbol::OpenParen))?;
     let mut params = Vec::new();
-    while let Some(token) = tokens.shift() {
+    loop {
+        let token = tokens.next_token()?;
         match token.lexeme {
             Lexeme::Symbol(Symbol::Comma) => continue,
             Lexeme::Symbol(Symbol::CloseParen) => break,
             Lexeme::Identifier(name) => params.push(name),
-            _ => return Err(SyntaxError::unexpected_token(token)),
+            _ => {
+                let text = tokens.text(&token);
+                return Err(SyntaxError::unexpected_token(token, text));
+            }
         }
     }
-    tokens.consume(Lexeme::Newline).unwrap();
-    let body = parse_block(tokens).unwrap();
+    let span = tokens.span_from(&start);
+    tokens.expect(Lexeme::Newline)?;
+    let body = parse_block(tokens, errors)?;
 
-    Ok(Node::Function { name, params, body })
+    Ok(Node::Function {
+        name,
+        params,
+        body,
+        doc,
+        span,
+    })
 }
 
-fn parse_if(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
+fn parse_if(tokens: &mut Tokens, errors: &mut Vec<SyntaxError>) -> Result<Node, SyntaxError> {
     // consumed `もし` or `もしくは`
-    _ = tokens.shift().unwrap();
+    let start = tokens.next_token()?.span;
     tokens.consume(Lexeme::Newline).ok();
     let is_indented = tokens.consume(Lexeme::Indent).is_ok();
-    let condition = Box::new(parse_expression(tokens).unwrap());
+    let condition = Box::new(parse_expression(tokens)?);
     if is_indented {
-        tokens.consume(Lexeme::Dedent).unwrap();
+        tokens.consume(Lexeme::Newline).ok();
+        tokens.expect(Lexeme::Dedent)?;
     };
 
-    tokens.consume(Lexeme::Keyword(Keyword::Then)).unwrap();
-    tokens.consume(Lexeme::Newline).unwrap();
-    let then_part = parse_block(tokens).unwrap();
+    tokens.expect(Lexeme::Keyword(Keyword::Then))?;
+    let span = tokens.span_from(&start);
+    tokens.expect(Lexeme::Newline)?;
+    let then_part = parse_block(tokens, errors)?;
 
     if let Some(token) = tokens.front() {
         if token.lexeme == Lexeme::Keyword(Keyword::Elif) {
             return Ok(Node::If {
                 condition,
                 then_part,
-                else_part: vec![parse_if(tokens).unwrap()],
+                else_part: vec![parse_if(tokens, errors)?],
+                span,
             });
         }
     }
 
     if tokens.consume(Lexeme::Keyword(Keyword::Else)).is_ok() {
-        tokens.consume(Lexeme::Newline).unwrap();
+        tokens.expect(Lexeme::Newline)?;
         Ok(Node::If {
             condition,
             then_part,
-            else_part: parse_block(tokens).unwrap(),
+            else_part: parse_block(tokens, errors)?,
+            span,
         })
     } else {
         Ok(Node::If {
             condition,
             then_part,
             else_part: Vec::new(),
+            span,
         })
     }
 }
 
 fn parse_return(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    tokens.consume(Lexeme::Keyword(Keyword::Return)).unwrap();
-    if tokens.front().unwrap().lexeme == Lexeme::Newline {
-        return Ok(Node::Return(Box::new(Node::Null)));
+    let start = tokens.next_span();
+    tokens.expect(Lexeme::Keyword(Keyword::Return))?;
+    if tokens.front().map(|token| &token.lexeme) == Some(&Lexeme::Newline) {
+        let value = Node::Null(start.end_point());
+        return Ok(Node::Return(Box::new(value), start));
     }
-    let value = parse_expression(tokens).unwrap();
-    Ok(Node::Return(Box::new(value)))
+    let value = parse_expression(tokens)?;
+    Ok(Node::Return(Box::new(value), tokens.span_from(&start)))
 }
 
-fn parse_loop(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    tokens.consume(Lexeme::Keyword(Keyword::Loop)).unwrap();
-    tokens.consume(Lexeme::Newline).unwrap();
+fn parse_loop(tokens: &mut Tokens, errors: &mut Vec<SyntaxError>) -> Result<Node, SyntaxError> {
+    let span = tokens.next_span();
+    tokens.expect(Lexeme::Keyword(Keyword::Loop))?;
+    tokens.expect(Lexeme::Newline)?;
     Ok(Node::Loop {
-        body: parse_block(tokens).unwrap(),
+        body: parse_block(tokens, errors)?,
+        span,
     })
 }
 
 fn parse_continue(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    tokens.consume(Lexeme::Keyword(Keyword::Continue)).unwrap();
-    Ok(Node::Continue)
+    let span = tokens.next_span();
+    tokens.expect(Lexeme::Keyword(Keyword::Continue))?;
+    Ok(Node::Continue(span))
 }
 
 fn parse_break(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    tokens.consume(Lexeme::Keyword(Keyword::Break)).unwrap();
-    Ok(Node::Break)
+    let span = tokens.next_span();
+    tokens.expect(Lexeme::Keyword(Keyword::Break))?;
+    Ok(Node::Break(span))
 }
 
 fn parse_print(tokens: &mut Tokens) -> Result<Node, SyntaxError> {
-    tokens.consume(Lexeme::Keyword(Keyword::Print)).unwrap();
-    tokens.consume(Lexeme::Symbol(Symbol::OpenParen)).unwrap();
-    let value = parse_expression(tokens).unwrap();
-    tokens.consume(Lexeme::Symbol(Symbol::CloseParen)).unwrap();
-    Ok(Node::Print(Box::new(value)))
+    let start = tokens.next_span();
+    tokens.expect(Lexeme::Keyword(Keyword::Print))?;
+    tokens.expect(Lexeme::Symbol(Symbol::OpenParen))?;
+    let value = parse_expression(tokens)?;
+    tokens.expect(Lexeme::Symbol(Symbol::CloseParen))?;
+    Ok(Node::Print(Box::new(value), tokens.span_from(&start)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::{
+        ast::{node::Node, Ast},
+        tokens::Tokens,
+    };
+    use std::str::FromStr;
+
+    /// Returns the name and documentation of each function in `source`.
+    fn docs(source: &str) -> Vec<(String, Option<String>)> {
+        let tokens = Tokens::from_str(source).expect("the source scans");
+        let ast = Ast::try_from(tokens).expect("the source parses");
+        ast.iter()
+            .filter_map(|node| match node {
+                Node::Function { name, doc, .. } => Some((name.clone(), doc.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn attaches_doc_comments_to_functions() {
+        let source = "＃＃ 二倍にする\n＃＃ ａ は数値\n関数 倍（ａ）\n    かえす ａ ＊ ２\n\n＃＃ 説明\n\nｘ ＝ １\n関数 無言（）\n    かえす 無\n";
+        assert_eq!(
+            docs(source),
+            [
+                ("倍".to_string(), Some("二倍にする\nａ は数値".to_string())),
+                ("無言".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_a_broken_interpolation_at_its_braces() {
+        let tokens = Tokens::from_str("表示（”a｛１＋｝b”）\n").expect("the source scans");
+        let errors = Ast::try_from(tokens).expect_err("the interpolation is broken");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, "S0008");
+        let span = errors[0].span.as_ref().unwrap();
+        assert_eq!((span.start.column, span.end.column), (6, 10));
+    }
+
+    #[test]
+    fn reports_every_invalid_symbol() {
+        let tokens = Tokens::from_str("もし ａ && ｂ なら\n    表示（１；）\nｘ ＝ ＠\n")
+            .expect("the source scans");
+        let errors = Ast::try_from(tokens).expect_err("the symbols are invalid");
+        let found = errors
+            .iter()
+            .map(|error| (error.code, error.span.as_ref().unwrap().start.line))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [("S0001", 1), ("S0001", 2), ("S0001", 3)]);
+    }
 }
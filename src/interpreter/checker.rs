@@ -0,0 +1,365 @@
+use super::{
+    ast::{node::Node, Ast},
+    error::{CheckError, RuntimeError},
+    normalize::normalize,
+    span::Span,
+    suggest,
+    tokens::{dictionary::Dictionary, keyword::Keyword},
+};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// Finds mistakes that would cause runtime errors, or be silently ignored,
+/// without running the program. Errors are returned in the order they appear.
+pub fn check(ast: &Ast, dictionary: &'static Dictionary) -> Vec<CheckError> {
+    let mut checker = Checker {
+        functions: HashMap::new(),
+        errors: Vec::new(),
+        dictionary,
+    };
+
+    for node in ast.iter() {
+        if let Node::Function { name, .. } = node {
+            match checker.functions.entry(normalize(name)) {
+                Entry::Occupied(_) => checker
+                    .errors
+                    .push(RuntimeError::redefining_function(node.clone())),
+                Entry::Vacant(entry) => _ = entry.insert(node),
+            }
+        }
+    }
+
+    let statements = ast
+        .iter()
+        .filter(|node| !matches!(node, Node::Function { .. }))
+        .collect::<Vec<_>>();
+    let mut scope = Scope::new(false);
+    scope
+        .assigned
+        .insert("引数".to_string(), "引数".to_string());
+    for node in &statements {
+        scope.collect_assignments(node);
+    }
+    for node in statements {
+        checker.check_statement(node, &scope);
+    }
+
+    for node in ast.iter() {
+        if let Node::Function { params, body, .. } = node {
+            let mut scope = Scope::new(true);
+            scope
+                .assigned
+                .extend(params.iter().map(|param| (normalize(param), param.clone())));
+            for node in body {
+                scope.collect_assignments(node);
+            }
+            for node in body {
+                checker.check_statement(node, &scope);
+            }
+        }
+    }
+
+    checker.errors.sort_by_key(|error| {
+        error
+            .trace
+            .span
+            .as_ref()
+            .map_or(usize::MAX, |span| span.start.char)
+    });
+    checker.errors.into_iter().map(CheckError).collect()
+}
+
+/// The variables visible to a piece of code, from their normalized names to the
+/// spelling they are assigned with. A function sees only its parameters and the
+/// variables it assigns, as it runs in an interpreter of its own.
+#[derive(Debug, Clone)]
+struct Scope {
+    assigned: HashMap<String, String>,
+    in_function: bool,
+    in_loop: bool,
+}
+
+impl Scope {
+    fn new(in_function: bool) -> Self {
+        Self {
+            assigned: HashMap::new(),
+            in_function,
+            in_loop: false,
+        }
+    }
+
+    /// Records the variables assigned by `node` on any path.
+    fn collect_assignments(&mut self, node: &Node) {
+        match node {
+            Node::Assignment { name, .. } => {
+                self.assigned.insert(normalize(name), name.clone());
+            }
+            Node::If {
+                then_part,
+                else_part,
+                ..
+            } => {
+                for node in then_part.iter().chain(else_part) {
+                    self.collect_assignments(node);
+                }
+            }
+            Node::Loop { body, .. } => {
+                for node in body {
+                    self.collect_assignments(node);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+struct Checker<'a> {
+    /// Functions by their normalized names.
+    functions: HashMap<String, &'a Node>,
+    errors: Vec<RuntimeError>,
+    /// The dictionary the keywords were read in, which also spells them in errors.
+    dictionary: &'static Dictionary,
+}
+
+impl Checker<'_> {
+    fn check_statement(&mut self, node: &Node, scope: &Scope) {
+        match node {
+            Node::Assignment { value, .. } => self.check_expression(value, scope),
+            Node::IndexAssignment {
+                target,
+                index,
+                value,
+                ..
+            } => {
+                self.check_expression(target, scope);
+                self.check_expression(index, scope);
+                self.check_expression(value, scope);
+            }
+            Node::Call { .. } => self.check_expression(node, scope),
+            Node::If {
+                condition,
+                then_part,
+                else_part,
+                ..
+            } => {
+                self.check_expression(condition, scope);
+                for node in then_part.iter().chain(else_part) {
+                    self.check_statement(node, scope);
+                }
+            }
+            Node::Loop { body, .. } => {
+                let scope = Scope {
+                    in_loop: true,
+                    ..scope.clone()
+                };
+                for node in body {
+                    self.check_statement(node, &scope);
+                }
+            }
+            Node::Return(value, _) => {
+                if !scope.in_function {
+                    self.errors.push(RuntimeError::return_outside_function(
+                        node.clone(),
+                        self.dictionary,
+                    ));
+                }
+                self.check_expression(value, scope);
+            }
+            Node::Break(_) | Node::Continue(_) => {
+                if !scope.in_loop {
+                    self.errors.push(RuntimeError::break_outside_loop(
+                        node.clone(),
+                        self.dictionary,
+                    ));
+                }
+            }
+            Node::Print(value, _) => self.check_expression(value, scope),
+            _ => {
+                self.errors
+                    .push(RuntimeError::unexpected_node(node.clone()));
+                self.check_expression(node, scope);
+            }
+        }
+    }
+
+    fn check_expression(&mut self, node: &Node, scope: &Scope) {
+        match node {
+            Node::Number(..) | Node::String(..) | Node::Bool(..) | Node::Null(_) => (),
+            Node::Variable(name, span) => self.check_variable(name, span.clone(), scope),
+            Node::Index { target, index, .. } => {
+                self.check_expression(target, scope);
+                self.check_expression(index, scope);
+            }
+            Node::Call { name, args, .. } => {
+                match self.functions.get(&normalize(name)) {
+                    Some(Node::Function { params, .. }) if params.len() != args.len() => {
+                        self.errors.push(RuntimeError::wrong_number_of_arguments(
+                            node.clone(),
+                            params.len(),
+                        ));
+                    }
+                    Some(_) => (),
+                    None => {
+                        let names = self
+                            .functions
+                            .values()
+                            .filter_map(|function| match function {
+                                Node::Function { name, .. } => Some(name.as_str()),
+                                _ => None,
+                            });
+                        let suggestion = suggest::similar(
+                            name,
+                            names.chain(
+                                Keyword::BUILTINS.map(|keyword| self.dictionary.spelling(&keyword)),
+                            ),
+                        );
+                        self.errors
+                            .push(RuntimeError::undefined_function(node.clone(), suggestion));
+                    }
+                }
+                for arg in args {
+                    self.check_expression(arg, scope);
+                }
+            }
+            Node::Template(parts, _) => {
+                for part in parts {
+                    self.check_expression(part, scope);
+                }
+            }
+            Node::Array(value, _)
+            | Node::Not(value, _)
+            | Node::Length(value, _)
+            | Node::Return(value, _)
+            | Node::Print(value, _) => self.check_expression(value, scope),
+            Node::Or { left, right, .. }
+            | Node::And { left, right, .. }
+            | Node::Equal { left, right, .. }
+            | Node::NotEqual { left, right, .. }
+            | Node::LessThan { left, right, .. }
+            | Node::LessThanOrEqual { left, right, .. }
+            | Node::GreaterThan { left, right, .. }
+            | Node::GreaterThanOrEqual { left, right, .. }
+            | Node::Add { left, right, .. }
+            | Node::Subtract { left, right, .. }
+            | Node::Multiply { left, right, .. }
+            | Node::Divide { left, right, .. }
+            | Node::FloorDivide { left, right, .. }
+            | Node::Modulo { left, right, .. }
+            | Node::Power {
+                base: left,
+                exponent: right,
+                ..
+            } => {
+                self.check_expression(left, scope);
+                self.check_expression(right, scope);
+            }
+            Node::Assignment { .. }
+            | Node::IndexAssignment { .. }
+            | Node::Function { .. }
+            | Node::If { .. }
+            | Node::Loop { .. }
+            | Node::Break(_)
+            | Node::Continue(_) => (),
+        }
+    }
+
+    /// Reports a read of the variable `name` that is never assigned in `scope`.
+    fn check_variable(&mut self, name: &str, span: Span, scope: &Scope) {
+        if scope.assigned.contains_key(&normalize(name)) {
+            return;
+        }
+        let names = scope.assigned.values().map(String::as_str);
+        let suggestion = suggest::similar(
+            name,
+            names.chain(Keyword::VALUES.map(|keyword| self.dictionary.spelling(&keyword))),
+        );
+        let node = Node::Variable(name.to_string(), span);
+        self.errors
+            .push(RuntimeError::undefined_variable(node, suggestion));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokens::{dictionary::STANDARD, Tokens};
+    use std::str::FromStr;
+
+    /// Returns the code and the line of each mistake found in `source`.
+    fn check_source(source: &str) -> Vec<(&'static str, usize)> {
+        let tokens = Tokens::from_str(source).expect("the source scans");
+        let ast = Ast::try_from(tokens).expect("the source parses");
+        check(&ast, &STANDARD)
+            .into_iter()
+            .map(|CheckError(error)| (error.code, error.trace.span.unwrap().start.line))
+            .collect()
+    }
+
+    #[test]
+    fn finds_each_kind_of_mistake() {
+        let source = "\
+関数 足す（ａ、ｂ）
+    かえす ａ ＋ ｂ
+関数 足す（ａ）
+    かえす ａ
+表示（足す（１））
+表示（引く（１、２））
+表示（ｙ）
+ぬける
+かえす １
+１ ＋ ２
+";
+        assert_eq!(
+            check_source(source),
+            [
+                ("R0007", 3),
+                ("R0008", 5),
+                ("R0006", 6),
+                ("R0005", 7),
+                ("R0015", 8),
+                ("R0016", 9),
+                ("R0001", 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_nothing_in_a_correct_program() {
+        let source = "\
+関数 階乗（ｎ）
+    もし ｎ ＝＝ ０ なら
+        かえす １
+    かえす ｎ ＊ 階乗（ｎ ー １）
+ｉ ＝ ０
+くりかえし
+    ｉ ＝ ｉ ＋ １
+    もし ｉ ＞ ３ なら
+        ぬける
+    表示（階乗（ｉ））
+";
+        assert_eq!(check_source(source), []);
+    }
+
+    #[test]
+    fn accepts_a_variable_assigned_in_only_one_branch() {
+        let source = "\
+もし 長さ（引数） ＞ ０ なら
+    名前 ＝ 引数「０」
+ちがえば
+    表示（”名前がありません”）
+表示（名前）
+";
+        assert_eq!(check_source(source), []);
+    }
+
+    #[test]
+    fn keeps_the_variables_of_functions_apart() {
+        let source = "\
+ｘ ＝ １
+関数 ｆ（ａ）
+    ｙ ＝ ａ
+    かえす ｘ
+表示（ｆ（ｙ））
+";
+        assert_eq!(check_source(source), [("R0005", 4), ("R0005", 5)]);
+    }
+}
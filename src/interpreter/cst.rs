@@ -0,0 +1,343 @@
+//! The concrete syntax tree, which keeps every character of the source.
+//!
+//! Spaces, comments and the line breaks that do not end a statement are kept as
+//! trivia on the tokens next to them, so the source can be written out again exactly
+//! as it was read. The tree also holds every token the parser reads, so it can be
+//! lowered into an [`Ast`].
+
+use super::{
+    ast::Ast,
+    error::SyntaxError,
+    tokens::{
+        dictionary::Dictionary, indentation::Indentation, lexeme::Lexeme, scan, symbol::Symbol,
+        token::Token, Tokens,
+    },
+};
+use std::{fmt, iter::Peekable, rc::Rc, vec};
+
+#[derive(Debug, Clone)]
+pub struct Cst {
+    pub root: SyntaxNode,
+    /// The trivia after the last token, such as comments at the end of the file.
+    pub end: Vec<Trivia>,
+    /// The dictionary the keywords were read in.
+    dictionary: &'static Dictionary,
+    /// The errors found while scanning, each with an invalid token in its place.
+    errors: Vec<SyntaxError>,
+    /// The source the tree was built from.
+    source: Rc<str>,
+}
+
+/// The whole program, one statement up to and including its `Newline`, an indented
+/// block from its `Indent` to its `Dedent`, or a part of a statement between `（` and
+/// `）` or `「` and `」`. A statement that opens a block, such as `もし … なら`, also
+/// holds the block.
+#[derive(Debug, Clone)]
+pub struct SyntaxNode {
+    pub children: Vec<SyntaxElement>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+/// A token the parser reads, with the trivia around it. Tokens added by the offside
+/// rule, such as `Indent`, have no text.
+#[derive(Debug, Clone)]
+pub struct SyntaxToken {
+    pub token: Token,
+    pub text: String,
+    /// The trivia on the lines above the token and before it on its own line.
+    pub leading: Vec<Trivia>,
+    /// The trivia after the token up to the end of its line.
+    pub trailing: Vec<Trivia>,
+}
+
+/// Spaces, a comment, a line continuation or a line break that the parser skips.
+#[derive(Debug, Clone)]
+pub struct Trivia {
+    pub token: Token,
+    pub text: String,
+}
+
+impl Cst {
+    /// Builds the tree for `source`. `file` is recorded in the span of every token,
+    /// and keywords are read in `dictionary`.
+    pub fn parse(
+        source: &str,
+        file: Option<&str>,
+        indentation: &Indentation,
+        dictionary: &'static Dictionary,
+    ) -> Result<Self, SyntaxError> {
+        let (scanned, errors) = scan(source, file, dictionary);
+        let mut tokens =
+            Tokens::from_scanned(source, scanned.clone(), Vec::new(), indentation, dictionary)?;
+        let text = |token: &Token| source[token.span.start.byte..token.span.end.byte].to_string();
+
+        let mut scanned = scanned.into_iter();
+        let mut syntax_tokens: Vec<SyntaxToken> = Vec::new();
+        // The last token with text, which takes the trivia after it on its line.
+        let mut last = None;
+        while let Some(token) = tokens.shift() {
+            let is_added = matches!(
+                token.lexeme,
+                Lexeme::Indent | Lexeme::Dedent | Lexeme::DocComment(_)
+            ) || token.span.start == token.span.end;
+            if is_added {
+                syntax_tokens.push(SyntaxToken::new(token, String::new(), Vec::new()));
+                continue;
+            }
+
+            let mut trivia = Vec::new();
+            for skipped in scanned.by_ref() {
+                if skipped.span == token.span {
+                    break;
+                }
+                trivia.push(Trivia {
+                    text: text(&skipped),
+                    token: skipped,
+                });
+            }
+            let leading = match last {
+                Some(index) => attach_trailing(&mut syntax_tokens[index], trivia),
+                None => trivia,
+            };
+            last = Some(syntax_tokens.len());
+            let text = text(&token);
+            syntax_tokens.push(SyntaxToken::new(token, text, leading));
+        }
+
+        let end = scanned
+            .map(|token| Trivia {
+                text: text(&token),
+                token,
+            })
+            .collect();
+        let mut tokens = syntax_tokens.into_iter().peekable();
+        let mut children = Vec::new();
+        while tokens.peek().is_some() {
+            children.push(build_statement(&mut tokens));
+        }
+        Ok(Self {
+            root: SyntaxNode { children },
+            end,
+            dictionary,
+            errors,
+            source: Rc::from(source),
+        })
+    }
+
+    /// Takes the tokens the parser reads out of the tree, in order.
+    pub fn tokens(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        self.root.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    /// Parses the tokens in the tree, recovering from syntax errors like [`Ast::parse`].
+    /// The errors include the ones found while scanning.
+    pub fn lower(&self) -> (Ast, Vec<SyntaxError>) {
+        let tokens = Tokens::arranged(
+            self.source.clone(),
+            self.tokens(),
+            self.errors.clone(),
+            self.dictionary,
+        );
+        Ast::parse(tokens)
+    }
+}
+
+impl SyntaxNode {
+    fn collect_tokens(&self, tokens: &mut Vec<Token>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token.token.clone()),
+            }
+        }
+    }
+}
+
+impl SyntaxToken {
+    fn new(token: Token, text: String, leading: Vec<Trivia>) -> Self {
+        Self {
+            token,
+            text,
+            leading,
+            trailing: Vec::new(),
+        }
+    }
+}
+
+/// Gives `token` the trivia up to the first line break, unless the token itself ends
+/// the line. Returns the rest, which leads the next token.
+fn attach_trailing(token: &mut SyntaxToken, mut trivia: Vec<Trivia>) -> Vec<Trivia> {
+    if token.token.lexeme == Lexeme::Newline {
+        return trivia;
+    }
+    let line_end = trivia
+        .iter()
+        .position(|trivia| trivia.token.lexeme == Lexeme::Newline)
+        .unwrap_or(trivia.len());
+    let rest = trivia.split_off(line_end);
+    token.trailing = trivia;
+    rest
+}
+
+type SyntaxTokens = Peekable<vec::IntoIter<SyntaxToken>>;
+
+/// Builds one statement, with the block under it if it opens one.
+fn build_statement(tokens: &mut SyntaxTokens) -> SyntaxElement {
+    let mut children = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token.token.lexeme {
+            Lexeme::Newline => {
+                children.push(SyntaxElement::Token(token));
+                if tokens
+                    .peek()
+                    .is_some_and(|token| token.token.lexeme == Lexeme::Indent)
+                {
+                    children.push(build_block(tokens));
+                }
+                break;
+            }
+            Lexeme::Indent | Lexeme::Dedent if children.is_empty() => {
+                children.push(SyntaxElement::Token(token));
+                break;
+            }
+            Lexeme::Symbol(Symbol::OpenParen | Symbol::OpenBracket) => {
+                children.push(build_group(token, tokens));
+            }
+            _ => children.push(SyntaxElement::Token(token)),
+        }
+        if tokens
+            .peek()
+            .is_some_and(|token| token.token.lexeme == Lexeme::Dedent)
+        {
+            break;
+        }
+    }
+    SyntaxElement::Node(SyntaxNode { children })
+}
+
+/// Builds a block from its `Indent` to its `Dedent`.
+fn build_block(tokens: &mut SyntaxTokens) -> SyntaxElement {
+    let mut children = tokens
+        .next()
+        .map(SyntaxElement::Token)
+        .into_iter()
+        .collect::<Vec<_>>();
+    while let Some(token) = tokens.peek() {
+        if token.token.lexeme == Lexeme::Dedent {
+            children.extend(tokens.next().map(SyntaxElement::Token));
+            break;
+        }
+        children.push(build_statement(tokens));
+    }
+    SyntaxElement::Node(SyntaxNode { children })
+}
+
+/// Builds a group from `open` to the bracket that closes it, or to the end of the line.
+fn build_group(open: SyntaxToken, tokens: &mut SyntaxTokens) -> SyntaxElement {
+    let close = match open.token.lexeme {
+        Lexeme::Symbol(Symbol::OpenParen) => Lexeme::Symbol(Symbol::CloseParen),
+        _ => Lexeme::Symbol(Symbol::CloseBracket),
+    };
+    let mut children = vec![SyntaxElement::Token(open)];
+    while let Some(token) = tokens.peek() {
+        match &token.token.lexeme {
+            Lexeme::Newline => break,
+            Lexeme::Symbol(Symbol::OpenParen | Symbol::OpenBracket) => {
+                let open = tokens.next().expect("peeked");
+                children.push(build_group(open, tokens));
+            }
+            lexeme => {
+                let is_close = *lexeme == close;
+                children.extend(tokens.next().map(SyntaxElement::Token));
+                if is_close {
+                    break;
+                }
+            }
+        }
+    }
+    SyntaxElement::Node(SyntaxNode { children })
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root)?;
+        self.end
+            .iter()
+            .try_for_each(|trivia| write!(f, "{}", trivia))
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.children.iter().try_for_each(|child| match child {
+            SyntaxElement::Node(node) => write!(f, "{}", node),
+            SyntaxElement::Token(token) => write!(f, "{}", token),
+        })
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.leading
+            .iter()
+            .try_for_each(|trivia| write!(f, "{}", trivia))?;
+        write!(f, "{}", self.text)?;
+        self.trailing
+            .iter()
+            .try_for_each(|trivia| write!(f, "{}", trivia))
+    }
+}
+
+impl fmt::Display for Trivia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokens::dictionary::STANDARD;
+    use std::str::FromStr;
+
+    const SAMPLES: [&str; 8] = [
+        include_str!("../../Shikibu/main.skb"),
+        "＃ 先頭のコメント\n\n#{ ブロック\n  コメント }#\nｘ ＝ １  ＃ 行末のコメント\n",
+        "もし ｘ ＞ １ なら\n    表示（ｘ）\n\n    ｙ ＝ （１ ＋\n        ２）\nちがえば\n  　表示（”ｘ は｛ｘ｝”）\n",
+        "＃＃ 二倍にする\n関数 倍（ａ）\n    かえす ａ ＊ ２ ￥\n        ＋ ０\n表示（倍（２））",
+        "ｘ ＝ １\r\nもし 真 なら\r\n\t表示（ｘ）\r\n",
+        "ｓ ＝ ”””\n    一行目\n    二行目\n    ”””\n表示（『生の｛文字列｝』）   \n",
+        "表示（１）\n\n   \n＃ 最後のコメント",
+        "＃＃ 説明だけ",
+    ];
+
+    #[test]
+    fn re_emits_the_source() {
+        for source in SAMPLES {
+            let cst = Cst::parse(source, None, &Indentation::default(), &STANDARD)
+                .expect("the sample scans");
+            assert_eq!(cst.to_string(), source);
+        }
+    }
+
+    #[test]
+    fn lowers_to_the_same_tokens_as_the_parser_reads() {
+        for source in SAMPLES {
+            let cst = Cst::parse(source, None, &Indentation::default(), &STANDARD)
+                .expect("the sample scans");
+            let mut tokens = Tokens::from_str(source).expect("the sample scans");
+            let mut expected = Vec::new();
+            while let Some(token) = tokens.shift() {
+                expected.push(token);
+            }
+            assert_eq!(cst.tokens(), expected);
+        }
+    }
+}
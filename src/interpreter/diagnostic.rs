@@ -0,0 +1,360 @@
+use super::{
+    error::{CheckError, Frame, RuntimeError, Suggestion, SyntaxError},
+    json::Json,
+    span::{Location, Span},
+};
+use std::fmt::Write;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Number of calls shown at each end of a long backtrace, such as one of deep recursion.
+const BACKTRACE_EDGE: usize = 10;
+
+/// A message to the user about a problem in the program,
+/// built from a [`SyntaxError`], a [`RuntimeError`] or a [`CheckError`].
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// The kind of the problem, such as `構文エラー`.
+    pub title: String,
+    /// The error code explained by `shikibu --explain`, such as `S0003`.
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub span: Option<Span>,
+    pub notes: Vec<String>,
+    pub hints: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+    /// The function calls that led to the problem, the innermost first.
+    pub backtrace: Vec<Frame>,
+}
+
+impl From<&SyntaxError> for Diagnostic {
+    fn from(error: &SyntaxError) -> Self {
+        Self {
+            title: "構文エラー".to_string(),
+            code: Some(error.code),
+            message: error.message.clone(),
+            span: error.span.as_deref().cloned(),
+            notes: error.notes.clone(),
+            hints: error.hints.clone(),
+            suggestions: error.suggestions.clone(),
+            backtrace: Vec::new(),
+        }
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(error: &RuntimeError) -> Self {
+        Self {
+            title: "実行時エラー".to_string(),
+            code: Some(error.code),
+            message: error.message.clone(),
+            span: error.trace.span.clone(),
+            notes: error.notes.clone(),
+            hints: error.hints.clone(),
+            suggestions: error.suggestions.clone(),
+            backtrace: error.trace.backtrace.clone(),
+        }
+    }
+}
+
+impl From<&CheckError> for Diagnostic {
+    fn from(CheckError(error): &CheckError) -> Self {
+        Self {
+            title: "検査エラー".to_string(),
+            ..Self::from(error)
+        }
+    }
+}
+
+/// Converts a diagnostic into the object written by `--message-format=json`.
+impl From<&Diagnostic> for Json {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        let suggestions = diagnostic.suggestions.iter().map(|suggestion| {
+            Json::Object(vec![
+                ("message", suggestion.message.as_str().into()),
+                ("span", (&suggestion.span).into()),
+                ("replacement", suggestion.replacement.as_str().into()),
+            ])
+        });
+        let backtrace = diagnostic.backtrace.iter().map(|frame| {
+            Json::Object(vec![
+                ("function", frame.function.as_str().into()),
+                ("args", frame.args.clone().into()),
+                ("span", (&frame.span).into()),
+            ])
+        });
+        Json::Object(vec![
+            ("code", diagnostic.code.into()),
+            ("severity", "error".into()),
+            ("title", diagnostic.title.as_str().into()),
+            ("message", diagnostic.message.as_str().into()),
+            ("span", diagnostic.span.as_ref().into()),
+            ("notes", diagnostic.notes.clone().into()),
+            ("hints", diagnostic.hints.clone().into()),
+            ("suggestions", Json::Array(suggestions.collect())),
+            ("backtrace", Json::Array(backtrace.collect())),
+        ])
+    }
+}
+
+impl From<&Span> for Json {
+    fn from(span: &Span) -> Self {
+        Json::Object(vec![
+            ("file", span.file.as_deref().into()),
+            ("start", span.start.into()),
+            ("end", span.end.into()),
+        ])
+    }
+}
+
+impl From<Location> for Json {
+    fn from(location: Location) -> Self {
+        Json::Object(vec![
+            ("line", location.line.into()),
+            ("column", location.column.into()),
+            ("byte", location.byte.into()),
+            ("char", location.char.into()),
+        ])
+    }
+}
+
+/// Renders diagnostics with an excerpt of the source code, in the style of rustc:
+///
+/// ```text
+/// 実行時エラー[R0005]: 変数「ｙ」は定義されていません
+///  --> main.skb:3:5
+///   |
+/// 3 | 表示（ｙ＋１）
+///   |       ^^
+///   |
+///   = 呼び出し履歴（新しい順）:
+///       1: 関数（１） main.skb:7:1 で呼び出し
+///   = ヒント: ...
+///   = 説明: shikibu --explain R0005
+/// ```
+pub struct Renderer<'a> {
+    source: &'a str,
+    color: bool,
+    /// A tab in an excerpt advances to the next multiple of this many columns, as it
+    /// does when indentation is measured.
+    tab_width: usize,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(source: &'a str, color: bool, tab_width: usize) -> Self {
+        Self {
+            source,
+            color,
+            tab_width,
+        }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let accent = self.paint(RED);
+        let (blue, bold, reset) = (self.paint(BLUE), self.paint(BOLD), self.paint(RESET));
+        let mut output = String::new();
+
+        let code = diagnostic
+            .code
+            .map(|code| format!("[{}]", code))
+            .unwrap_or_default();
+        _ = writeln!(
+            output,
+            "{accent}{}{}{reset}: {bold}{}{reset}",
+            diagnostic.title, code, diagnostic.message
+        );
+
+        let span = diagnostic.span.as_ref().filter(|span| span.start.line > 0);
+        let excerpt = span.and_then(|span| Some((span, self.line(span.start.line)?)));
+        let gutter = match span {
+            Some(span) => " ".repeat(span.start.line.to_string().len()),
+            None => String::new(),
+        };
+
+        if let Some(span) = span {
+            _ = writeln!(output, "{gutter}{blue}-->{reset} {}", span);
+        }
+        if let Some((span, line)) = excerpt {
+            let (padding, width) = self.underline(line, span);
+            _ = writeln!(output, "{gutter} {blue}|{reset}");
+            _ = writeln!(
+                output,
+                "{blue}{} |{reset} {}",
+                span.start.line,
+                self.expand_tabs(line)
+            );
+            _ = writeln!(
+                output,
+                "{gutter} {blue}|{reset} {}{accent}{}{reset}",
+                " ".repeat(padding),
+                "^".repeat(width)
+            );
+        }
+
+        if !diagnostic.notes.is_empty()
+            || !diagnostic.hints.is_empty()
+            || !diagnostic.suggestions.is_empty()
+            || !diagnostic.backtrace.is_empty()
+            || diagnostic.code.is_some()
+        {
+            _ = writeln!(output, "{gutter} {blue}|{reset}");
+        }
+        if !diagnostic.backtrace.is_empty() {
+            _ = writeln!(
+                output,
+                "{gutter} {blue}={reset} {bold}呼び出し履歴（新しい順）{reset}:"
+            );
+            let count = diagnostic.backtrace.len();
+            for (i, frame) in diagnostic.backtrace.iter().enumerate() {
+                if count > BACKTRACE_EDGE * 2 && i >= BACKTRACE_EDGE && i < count - BACKTRACE_EDGE {
+                    if i == BACKTRACE_EDGE {
+                        let omitted = count - BACKTRACE_EDGE * 2;
+                        _ = writeln!(output, "{gutter}       ……（{omitted} 件省略）……");
+                    }
+                    continue;
+                }
+                _ = writeln!(output, "{gutter}     {:>3}: {}", i + 1, frame);
+            }
+        }
+        for note in &diagnostic.notes {
+            _ = writeln!(output, "{gutter} {blue}={reset} {bold}注{reset}: {}", note);
+        }
+        let suggestions = diagnostic.suggestions.iter().map(|s| &s.message);
+        for hint in diagnostic.hints.iter().chain(suggestions) {
+            _ = writeln!(
+                output,
+                "{gutter} {blue}={reset} {bold}ヒント{reset}: {}",
+                hint
+            );
+        }
+        if let Some(code) = diagnostic.code {
+            _ = writeln!(
+                output,
+                "{gutter} {blue}={reset} {bold}説明{reset}: shikibu --explain {}",
+                code
+            );
+        }
+        output
+    }
+
+    fn paint(&self, code: &'static str) -> &'static str {
+        if self.color {
+            code
+        } else {
+            ""
+        }
+    }
+
+    /// Returns the line with the given line number, which starts from 1.
+    fn line(&self, number: usize) -> Option<&'a str> {
+        self.source.lines().nth(number.checked_sub(1)?)
+    }
+
+    /// Returns the number of terminal columns before the span and the width of the span
+    /// within `line`. Spans continuing onto later lines are underlined to the end of the line.
+    fn underline(&self, line: &str, span: &Span) -> (usize, usize) {
+        let chars = line.chars().collect::<Vec<_>>();
+        let start = (span.start.column - 1).min(chars.len());
+        let end = if span.end.line == span.start.line {
+            (span.end.column - 1).clamp(start, chars.len())
+        } else {
+            chars.len()
+        };
+
+        let padding = self.width(&chars[..start], 0);
+        let width = self.width(&chars[start..end], padding) - padding;
+        (padding, usize::max(width, 1))
+    }
+
+    /// Returns the column reached after writing `chars` from column `column`.
+    fn width(&self, chars: &[char], column: usize) -> usize {
+        chars.iter().fold(column, |column, c| match c {
+            '\t' => (column / self.tab_width + 1) * self.tab_width,
+            c => column + display_width(*c),
+        })
+    }
+
+    /// Replaces each tab in `line` with spaces up to the next tab stop.
+    fn expand_tabs(&self, line: &str) -> String {
+        let mut expanded = String::new();
+        let mut column = 0;
+        for c in line.chars() {
+            let next = self.width(&[c], column);
+            if c == '\t' {
+                expanded.push_str(&" ".repeat(next - column));
+            } else {
+                expanded.push(c);
+            }
+            column = next;
+        }
+        expanded
+    }
+}
+
+/// Returns the number of columns `c` occupies in a terminal.
+/// Full-width characters such as `＝`, `「` and kanji occupy two columns.
+pub fn display_width(c: char) -> usize {
+    match c {
+        '\u{0300}'..='\u{036F}' | '\u{200B}'..='\u{200F}' | '\u{3099}'..='\u{309A}' => 0,
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{303E}'
+        | '\u{3041}'..='\u{33FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{A000}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1F64F}'
+        | '\u{1F900}'..='\u{1F9FF}'
+        | '\u{20000}'..='\u{3FFFD}' => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the span of the characters `start..end` on the only line of `source`.
+    fn span(source: &str, start: usize, end: usize) -> Span {
+        let advance = |n| {
+            source
+                .chars()
+                .take(n)
+                .fold(Location::start(), |location, c| location.advance(c))
+        };
+        Span::new(None, advance(start), advance(end))
+    }
+
+    #[test]
+    fn expands_tabs_to_the_configured_tab_stops() {
+        let source = "\tｘ\tｙ";
+        let diagnostic = Diagnostic {
+            title: "構文エラー".to_string(),
+            code: None,
+            message: String::new(),
+            span: Some(span(source, 3, 4)),
+            notes: Vec::new(),
+            hints: Vec::new(),
+            suggestions: Vec::new(),
+            backtrace: Vec::new(),
+        };
+
+        for (tab_width, line, caret) in [
+            (4, "    ｘ  ｙ", "        ^^"),
+            (2, "  ｘ  ｙ", "      ^^"),
+            (8, "        ｘ      ｙ", "                ^^"),
+        ] {
+            let output = Renderer::new(source, false, tab_width).render(&diagnostic);
+            let lines = output.lines().collect::<Vec<_>>();
+            assert_eq!(lines[3], format!("1 | {}", line), "{}", tab_width);
+            assert_eq!(lines[4], format!("  | {}", caret), "{}", tab_width);
+        }
+    }
+}
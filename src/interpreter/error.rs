@@ -1,74 +1,457 @@
-use super::{ast::node::Node, tokens::token::Token};
+use super::{
+    ast::node::Node,
+    span::Span,
+    suggest,
+    tokens::{
+        dictionary::Dictionary, indentation::Space, keyword::Keyword, lexeme::Lexeme, token::Token,
+    },
+};
+use std::fmt;
 
-#[derive(Debug)]
+pub mod catalog;
+
+#[derive(Debug, Clone)]
 pub struct SyntaxError {
+    /// A stable code such as `S0003`, explained by `shikibu --explain`.
+    pub code: &'static str,
     pub message: String,
-    pub token: Option<Token>,
+    pub span: Option<Box<Span>>,
+    pub notes: Vec<String>,
+    pub hints: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// The kinds of syntax errors that are acted on, rather than only reported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyntaxErrorKind {
+    /// A string literal runs into the end of its line or of the source.
+    UnterminatedString,
+    /// A bracket is still open at the end of the source.
+    UnclosedBracket,
+    /// The tokens end in the middle of a statement or an expression.
+    UnexpectedEnd,
+    Other,
 }
 
 impl SyntaxError {
-    pub fn invalid_char(c: char, position: usize) -> Self {
+    fn new(code: &'static str, message: String, span: Span) -> Self {
         Self {
-            message: format!("Invalid character: {}", c),
-            token: Some(Token::invalid(c, position)),
+            code,
+            message,
+            span: Some(Box::new(span)),
+            notes: Vec::new(),
+            hints: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
-    pub fn unexpected_token(token: Token) -> Self {
-        Self {
-            message: format!("Unexpected token: {:?}", token.lexeme),
-            token: Some(token),
+    pub fn kind(&self) -> SyntaxErrorKind {
+        match self.code {
+            "S0004" => SyntaxErrorKind::UnexpectedEnd,
+            "S0010" => SyntaxErrorKind::UnterminatedString,
+            "S0017" => SyntaxErrorKind::UnclosedBracket,
+            _ => SyntaxErrorKind::Other,
+        }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hints.push(hint.into());
+        self
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    /// `text` is one or two characters that have no meaning, such as `＠` or `&&`.
+    /// Keywords are suggested in `dictionary`.
+    pub fn invalid_char(text: &str, span: Span, dictionary: &Dictionary) -> Self {
+        let error = Self::new(
+            "S0001",
+            format!("使えない文字「{}」があります", text),
+            span.clone(),
+        );
+        match text {
+            "&" | "&&" | "＆" | "＆＆" | "&＆" | "＆&" => error.with_suggestion(
+                Suggestion::did_you_mean(span, dictionary.spelling(&Keyword::And)),
+            ),
+            "|" | "||" | "｜" | "｜｜" | "|｜" | "｜|" => error.with_suggestion(
+                Suggestion::did_you_mean(span, dictionary.spelling(&Keyword::Or)),
+            ),
+            "'" | "’" | "‘" | "`" => error.with_hint("文字列は「”」で囲んでください"),
+            ";" | "；" => error.with_hint("文を区切るには改行してください"),
+            ":" | "：" => error.with_hint(format!(
+                "ブロックは「{}」のあとで改行し、字下げして書いてください",
+                dictionary.spelling(&Keyword::Then)
+            )),
+            "{" | "}" | "｛" | "｝" => {
+                error.with_hint("「｛」と「｝」は文字列の中で式を埋め込むときに使います")
+            }
+            _ => error,
         }
     }
 
+    /// `text` is the token as it is written in the source.
+    pub fn unexpected_token(token: Token, text: &str) -> Self {
+        Self::new(
+            "S0002",
+            format!("ここに{}は書けません", describe_token(&token, text)),
+            token.span,
+        )
+    }
+
+    /// `text` is the token found as it is written in the source. The keyword expected
+    /// is spelled in `dictionary`.
+    pub fn expected(lexeme: &Lexeme, token: Token, text: &str, dictionary: &Dictionary) -> Self {
+        Self::new(
+            "S0003",
+            format!(
+                "{}が必要ですが、{}があります",
+                describe(lexeme, dictionary),
+                describe_token(&token, text)
+            ),
+            token.span,
+        )
+    }
+
+    pub fn unexpected_end_of_file(span: Span) -> Self {
+        Self::new(
+            "S0004",
+            "プログラムが途中で終わっています".to_string(),
+            span,
+        )
+    }
+
+    pub fn misspelled_keyword(token: Token, keyword: &Keyword, dictionary: &Dictionary) -> Self {
+        let span = token.span.clone();
+        Self::new(
+            "S0005",
+            format!("「{}」という文は書けません", token.lexeme),
+            token.span,
+        )
+        .with_suggestion(Suggestion::did_you_mean(span, dictionary.spelling(keyword)))
+    }
+
+    pub fn invalid_assignment_target(span: Span) -> Self {
+        Self::new("S0016", "ここには代入できません".to_string(), span)
+            .with_note(
+                "代入できるのは、変数と、盤「１」「２」のように変数に添字を付けたものだけです",
+            )
+            .with_hint("値を比べたいときは「＝＝」を使ってください")
+    }
+
     pub fn function_declaration_in_block(token: Token) -> Self {
-        Self {
-            message: "Cannot declare function in block".to_string(),
-            token: Some(token),
+        Self::new(
+            "S0006",
+            "ブロックの中では関数を定義できません".to_string(),
+            token.span,
+        )
+        .with_hint("関数は字下げせずに、いちばん外側で定義してください")
+    }
+
+    /// `detail` says what is wrong with the number literal `literal`.
+    pub fn malformed_number(literal: &str, detail: &str, span: Span) -> Self {
+        Self::new(
+            "S0007",
+            format!("数値「{}」の書き方がまちがっています", literal),
+            span,
+        )
+        .with_note(detail)
+    }
+
+    /// `detail` says what is wrong with the expression embedded in a string literal.
+    pub fn malformed_interpolation(detail: &str, span: Span) -> Self {
+        Self::new(
+            "S0008",
+            "文字列への式の埋め込みがまちがっています".to_string(),
+            span,
+        )
+        .with_note(detail)
+        .with_hint("「｛」や「｝」をそのまま書くには、「￥｛」「￥｝」と書いてください")
+    }
+
+    pub fn invalid_escape(sequence: &str, span: Span) -> Self {
+        Self::new(
+            "S0009",
+            format!("「{}」という書き方は文字列の中で使えません", sequence),
+            span,
+        )
+        .with_note("使えるのは ￥ｎ（改行）、￥ｔ（タブ）、￥ｒ、￥￥、￥”、￥｛、￥｝、￥ｕ｛３０４２｝（文字の番号）です")
+        .with_hint("「￥」をそのまま書くには「￥￥」と書いてください")
+    }
+
+    /// `closing` is the delimiter missing at the end, such as `”` or `』`.
+    pub fn unterminated_string(closing: &str, span: Span) -> Self {
+        Self::new("S0010", "文字列が閉じられていません".to_string(), span)
+            .with_hint(format!("文字列の終わりに「{}」を書いてください", closing))
+    }
+
+    /// `open` is the bracket left open, as it is written, and `span` is where it is.
+    pub fn unclosed_bracket(open: &str, span: Span) -> Self {
+        Self::new("S0017", format!("「{}」が閉じられていません", open), span).with_hint(format!(
+            "式の終わりに「{}」を書いてください",
+            closing_bracket(open)
+        ))
+    }
+
+    /// `open` is the bracket left open, as it is written, and `span` is where it is.
+    /// `keyword` starts a statement on line `line` while the bracket is still open.
+    pub fn unclosed_bracket_before(open: &str, keyword: &str, line: usize, span: Span) -> Self {
+        Self::new(
+            "S0018",
+            format!("「{}」が閉じられないまま、次の文が始まっています", open),
+            span,
+        )
+        .with_note(format!(
+            "{}行目の「{}」から新しい文が始まっています",
+            line, keyword
+        ))
+        .with_hint(format!(
+            "その前で「{}」を書いて閉じてください",
+            closing_bracket(open)
+        ))
+    }
+
+    pub fn unterminated_comment(span: Span) -> Self {
+        Self::new("S0012", "コメントが閉じられていません".to_string(), span)
+            .with_hint("「＃｛」で始めたコメントは「｝＃」で閉じてください")
+    }
+
+    /// `expected` are the widths of the enclosing blocks, and `actual` is the width
+    /// of the line, in columns.
+    pub fn inconsistent_indentation(expected: &[usize], actual: usize, span: Span) -> Self {
+        let expected = expected
+            .iter()
+            .map(|width| format!("{}桁", width))
+            .collect::<Vec<_>>();
+        Self::new("S0013", "インデントが揃っていません".to_string(), span).with_note(format!(
+            "字下げの幅は{}のどれかのはずですが、{}桁になっています",
+            expected.join("、"),
+            actual
+        ))
+    }
+
+    /// `first` is the kind of space first used for indentation, on line `first_line`.
+    pub fn mixed_indentation(kind: Space, first: Space, first_line: usize, span: Span) -> Self {
+        Self::new(
+            "S0014",
+            format!("字下げに{}と{}が混ざっています", first, kind),
+            span,
+        )
+        .with_note(format!(
+            "このファイルでは{}行目から{}で字下げしています",
+            first_line, first
+        ))
+        .with_hint(format!("字下げには{}だけを使ってください", first))
+    }
+
+    pub fn shallow_string_line(span: Span) -> Self {
+        Self::new(
+            "S0011",
+            "複数行の文字列の行が、閉じる記号より浅く字下げされています".to_string(),
+            span,
+        )
+        .with_note("各行の先頭から、閉じる記号と同じ字下げが取り除かれます")
+        .with_hint("文字列の中の行は、閉じる記号と同じかそれより深く字下げしてください")
+    }
+
+    pub fn unknown_dictionary(name: &str, span: Span) -> Self {
+        let names = Dictionary::ALL.map(|dictionary| dictionary.name);
+        let error = Self::new(
+            "S0015",
+            format!("「{}」というキーワードの辞書はありません", name),
+            span.clone(),
+        )
+        .with_note(format!(
+            "使える辞書は{}です",
+            Dictionary::ALL
+                .map(|dictionary| dictionary.to_string())
+                .join("、")
+        ));
+        match suggest::similar(name, names) {
+            Some(similar) => error.with_suggestion(Suggestion::did_you_mean(span, similar)),
+            None => error,
+        }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(span) = &self.span {
+            write!(f, "{}: ", span)?;
         }
+        write!(f, "構文エラー[{}]: {}", self.code, self.message)
     }
 }
 
+impl std::error::Error for SyntaxError {}
+
 #[derive(Debug)]
 pub struct RuntimeError {
+    /// A stable code such as `R0005`, explained by `shikibu --explain`.
+    pub code: &'static str,
     pub message: String,
-    pub node: Option<Node>,
+    pub notes: Vec<String>,
+    pub hints: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+    /// Boxed so that every `Result` carrying the error stays small.
+    pub trace: Box<Trace>,
+}
+
+/// Where a [`RuntimeError`] happened and how the program got there.
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub span: Option<Span>,
+    /// The function calls that led to the error, the innermost first.
+    pub backtrace: Vec<Frame>,
+}
+
+/// A function call in the backtrace of a [`RuntimeError`].
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub function: String,
+    /// The values of the arguments, formatted for display.
+    pub args: Vec<String>,
+    /// Where the function was called.
+    pub span: Span,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}（{}） {} で呼び出し",
+            self.function,
+            self.args.join("、"),
+            self.span
+        )
+    }
 }
 
 impl RuntimeError {
-    pub fn unexpected_node(node: Node) -> Self {
+    fn new(code: &'static str, message: String, span: Span) -> Self {
         Self {
-            message: format!("Unexpected node: {:?}", node),
-            node: Some(node),
+            code,
+            message,
+            notes: Vec::new(),
+            hints: Vec::new(),
+            suggestions: Vec::new(),
+            trace: Box::new(Trace {
+                span: Some(span),
+                backtrace: Vec::new(),
+            }),
         }
     }
 
-    pub fn string_addition(node: Node) -> Self {
-        Self {
-            message: "Cannot add strings".to_string(),
-            node: Some(node),
-        }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
     }
 
-    pub fn comparing_different_types(node: Node) -> Self {
-        Self {
-            message: "Cannot compare different types".to_string(),
-            node: Some(node),
-        }
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hints.push(hint.into());
+        self
     }
 
-    pub fn undefined_variable(node: Node) -> Self {
-        Self {
-            message: "Undefined variable".to_string(),
-            node: Some(node),
-        }
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
     }
 
-    pub fn undefined_function(node: Node) -> Self {
-        Self {
-            message: "Undefined function".to_string(),
-            node: Some(node),
-        }
+    /// Records that the error escaped the function call `frame`.
+    pub fn with_frame(mut self, frame: Frame) -> Self {
+        self.trace.backtrace.push(frame);
+        self
+    }
+
+    pub fn unexpected_node(node: Node) -> Self {
+        Self::new(
+            "R0001",
+            "ここでは実行できない文です".to_string(),
+            node.span().clone(),
+        )
+    }
+
+    /// `left` and `right` are the types of the operands, such as `数値`.
+    pub fn invalid_addition(node: Node, left: &str, right: &str) -> Self {
+        Self::new(
+            "R0002",
+            format!("{}と{}は足し算できません", left, right),
+            node.span().clone(),
+        )
+        .with_hint("「＋」で足せるのは、数値どうしか、文字列と数値や文字列の組み合わせです")
+    }
+
+    /// `left` and `right` are the types of the operands, such as `数値`.
+    pub fn invalid_arithmetic(node: Node, left: &str, right: &str) -> Self {
+        let operator = match &node {
+            Node::Subtract { .. } => "ー",
+            Node::Multiply { .. } => "＊",
+            Node::Divide { .. } => "／",
+            Node::FloorDivide { .. } => "／／",
+            Node::Modulo { .. } => "％",
+            Node::Power { .. } => "＾",
+            _ => unreachable!(),
+        };
+        Self::new(
+            "R0003",
+            format!("{}と{}では「{}」の計算ができません", left, right, operator),
+            node.span().clone(),
+        )
+        .with_hint(format!(
+            "「{}」で計算できるのは数値どうしだけです",
+            operator
+        ))
+    }
+
+    /// `left` and `right` are the types of the operands, such as `数値`.
+    pub fn comparing_different_types(node: Node, left: &str, right: &str) -> Self {
+        Self::new(
+            "R0004",
+            format!("{}と{}は大きさを比べられません", left, right),
+            node.span().clone(),
+        )
+        .with_hint("「＜」や「＞」で比べられるのは数値どうしだけです")
+    }
+
+    pub fn undefined_variable(node: Node, suggestion: Option<&str>) -> Self {
+        let name = match &node {
+            Node::Variable(name, _) => name,
+            _ => unreachable!(),
+        };
+        let span = node.span().clone();
+        let mut error = Self::new(
+            "R0005",
+            format!("変数「{}」は定義されていません", name),
+            span.clone(),
+        );
+        error
+            .suggestions
+            .extend(suggestion.map(|suggestion| Suggestion::did_you_mean(span, suggestion)));
+        error
+    }
+
+    pub fn undefined_function(node: Node, suggestion: Option<&str>) -> Self {
+        let name = match &node {
+            Node::Call { name, .. } => name,
+            _ => unreachable!(),
+        };
+        let span = node.span().prefix(name);
+        let mut error = Self::new(
+            "R0006",
+            format!("関数「{}」は定義されていません", name),
+            node.span().clone(),
+        );
+        error
+            .suggestions
+            .extend(suggestion.map(|suggestion| Suggestion::did_you_mean(span, suggestion)));
+        error
     }
 
     pub fn redefining_function(node: Node) -> Self {
@@ -76,33 +459,263 @@ impl RuntimeError {
             Node::Function { name, .. } => name,
             _ => unreachable!(),
         };
-        Self {
-            message: format!("Redefining function: {:?}", name),
-            node: Some(node),
-        }
+        Self::new(
+            "R0007",
+            format!("関数「{}」はすでに定義されています", name),
+            node.span().clone(),
+        )
+        .with_hint("同じ名前の関数は一つしか定義できません。どちらかの名前を変えてください")
     }
 
-    pub fn wrong_number_of_arguments(node: Node) -> Self {
-        Self {
-            message: "Wrong number of arguments".to_string(),
-            node: Some(node),
-        }
+    /// `expected` is the number of parameters of the function.
+    pub fn wrong_number_of_arguments(node: Node, expected: usize) -> Self {
+        let (name, actual) = match &node {
+            Node::Call { name, args, .. } => (name, args.len()),
+            _ => unreachable!(),
+        };
+        Self::new(
+            "R0008",
+            format!(
+                "関数「{}」の引数は{}個ですが、{}個渡されています",
+                name, expected, actual
+            ),
+            node.span().clone(),
+        )
     }
 
-    pub fn has_no_length(node: Node) -> Self {
-        Self {
-            message: "Has no length".to_string(),
-            node: Some(node),
+    /// `limit` is the number of calls that may be nested, which `node` goes beyond.
+    pub fn call_too_deep(node: Node, limit: usize, dictionary: &Dictionary) -> Self {
+        let name = match &node {
+            Node::Call { name, .. } => name,
+            _ => unreachable!(),
+        };
+        Self::new(
+            "R0019",
+            "関数の呼び出しが深くなりすぎました".to_string(),
+            node.span().clone(),
+        )
+        .with_note(format!(
+            "関数「{}」を呼び出すと、呼び出しの中の呼び出しが{}回を超えます",
+            name, limit
+        ))
+        .with_hint(format!(
+            "関数が自分を呼び出すときは、「{}」で呼び出しをやめる場合を作ってください",
+            dictionary.spelling(&Keyword::If)
+        ))
+    }
+
+    /// `type_name` is the type of the value, such as `数値`.
+    pub fn has_no_length(node: Node, type_name: &str) -> Self {
+        Self::new(
+            "R0009",
+            format!("{}には長さがありません", type_name),
+            node.span().clone(),
+        )
+        .with_hint("長さを調べられるのは文字列と配列だけです")
+    }
+
+    /// `span` is the index, and `length` the length of the array.
+    pub fn index_out_of_range(index: f64, length: usize, span: Span) -> Self {
+        Self::new(
+            "R0010",
+            format!("添字{}は配列の範囲外です（長さ：{}）", index, length),
+            span,
+        )
+        .with_hint(format!("この配列の添字には０から{}までが使えます", length))
+    }
+
+    /// `type_name` is the type of the value, such as `数値`.
+    pub fn not_a_boolean(node: Node, type_name: &str) -> Self {
+        Self::new(
+            "R0011",
+            format!(
+                "「！」は真偽値にしか使えませんが、{}が渡されています",
+                type_name
+            ),
+            node.span().clone(),
+        )
+    }
+
+    /// `operand` is the left or right side of `operator`, `かつ` or `または`, and
+    /// `type_name` is the type of its value.
+    pub fn non_boolean_operand(
+        operator: &Node,
+        operand: Node,
+        type_name: &str,
+        dictionary: &Dictionary,
+    ) -> Self {
+        let keyword = match operator {
+            Node::And { .. } => Keyword::And,
+            Node::Or { .. } => Keyword::Or,
+            _ => unreachable!(),
+        };
+        Self::new(
+            "R0018",
+            format!(
+                "「{}」の左右には真偽値しか書けませんが、{}が渡されています",
+                dictionary.spelling(&keyword),
+                type_name
+            ),
+            operand.span().clone(),
+        )
+        .with_hint("「＝＝」や「＜」などで比べた結果を組み合わせてください")
+    }
+
+    /// `type_name` is the type of the index, such as `文字列`.
+    pub fn invalid_index(node: Node, type_name: &str) -> Self {
+        Self::new(
+            "R0012",
+            format!(
+                "配列の添字は数値でなければなりませんが、{}が使われています",
+                type_name
+            ),
+            node.span().clone(),
+        )
+    }
+
+    /// `target` is what the index is put on, and `type_name` the type of its value,
+    /// such as `数値`.
+    pub fn not_an_array(target: &Node, type_name: &str) -> Self {
+        let target_name = match target {
+            Node::Variable(name, _) => format!("変数「{}」", name),
+            _ => "この値".to_string(),
+        };
+        Self::new(
+            "R0013",
+            format!(
+                "{}は配列ではなく{}なので、添字を付けられません",
+                target_name, type_name
+            ),
+            target.span().clone(),
+        )
+    }
+
+    /// `type_name` is the type of the length, such as `文字列`.
+    pub fn invalid_array_length(node: Node, type_name: &str) -> Self {
+        Self::new(
+            "R0014",
+            format!(
+                "配列の長さは数値でなければなりませんが、{}が使われています",
+                type_name
+            ),
+            node.span().clone(),
+        )
+    }
+
+    /// `node` is `ぬける` or `つぎへ`.
+    pub fn break_outside_loop(node: Node, dictionary: &Dictionary) -> Self {
+        let keyword = match &node {
+            Node::Break(_) => Keyword::Break,
+            Node::Continue(_) => Keyword::Continue,
+            _ => unreachable!(),
+        };
+        Self::new(
+            "R0015",
+            format!(
+                "「{}」は「{}」の中でしか使えません",
+                dictionary.spelling(&keyword),
+                dictionary.spelling(&Keyword::Loop)
+            ),
+            node.span().clone(),
+        )
+    }
+
+    /// `node` is a division or a remainder whose right side is zero.
+    pub fn division_by_zero(node: Node, dictionary: &Dictionary) -> Self {
+        let hint = match &node {
+            Node::Modulo { .. } => "０で割った余りは求められません",
+            _ => "０で割ることはできません",
+        };
+        Self::new(
+            "R0017",
+            "０で割ろうとしています".to_string(),
+            node.span().clone(),
+        )
+        .with_hint(format!(
+            "{}。割る数が０にならないか、割る前に「{}」で確かめてください",
+            hint,
+            dictionary.spelling(&Keyword::If)
+        ))
+    }
+
+    pub fn return_outside_function(node: Node, dictionary: &Dictionary) -> Self {
+        Self::new(
+            "R0016",
+            format!(
+                "「{}」は関数の中でしか使えません",
+                dictionary.spelling(&Keyword::Return)
+            ),
+            node.span().clone(),
+        )
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(span) = &self.trace.span {
+            write!(f, "{}: ", span)?;
         }
+        write!(f, "実行時エラー[{}]: {}", self.code, self.message)
     }
+}
+
+impl std::error::Error for RuntimeError {}
+
+/// A mistake `shikibu check` finds without running the program: the runtime error the
+/// program would stop with when it reaches that code.
+#[derive(Debug)]
+pub struct CheckError(pub RuntimeError);
 
-    pub fn index_out_of_range(name: &str, index: usize) -> Self {
+/// A fix for an error, replacing the source code in `span` with `replacement`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Suggestion {
+    /// Suggests `replacement` for the name in `span`, which looks like a misspelling of it.
+    pub fn did_you_mean(span: Span, replacement: &str) -> Self {
         Self {
-            message: format!(
-                "配列の範囲外に値を代入しようとしています。配列： {} 添字：{}",
-                name, index
-            ),
-            node: None,
+            message: format!("「{}」のまちがいではありませんか？", replacement),
+            span,
+            replacement: replacement.to_string(),
         }
     }
 }
+
+/// Returns the bracket that closes `open`, in the same width.
+fn closing_bracket(open: &str) -> &'static str {
+    match open {
+        "(" => ")",
+        "[" => "]",
+        "［" => "］",
+        "「" => "」",
+        _ => "）",
+    }
+}
+
+/// Describes a lexeme the parser expects for messages, e.g. `「なら」` or `改行`.
+/// Keywords are spelled in `dictionary`.
+fn describe(lexeme: &Lexeme, dictionary: &Dictionary) -> String {
+    match lexeme {
+        Lexeme::Newline => "改行".to_string(),
+        Lexeme::Indent => "字下げ".to_string(),
+        Lexeme::Dedent => "字下げの終わり".to_string(),
+        Lexeme::Keyword(keyword) => format!("「{}」", dictionary.spelling(keyword)),
+        _ => format!("「{}」", lexeme),
+    }
+}
+
+/// Describes a token found in the source for messages, e.g. `「０ｘＦＦ」` or
+/// `文字列”あ”`, quoting `text`, the token as the student wrote it.
+fn describe_token(token: &Token, text: &str) -> String {
+    match token.lexeme {
+        Lexeme::Newline => "改行".to_string(),
+        Lexeme::Indent => "字下げ".to_string(),
+        Lexeme::Dedent => "字下げの終わり".to_string(),
+        Lexeme::String(_) | Lexeme::Template(_) => format!("文字列{}", text),
+        _ => format!("「{}」", text),
+    }
+}
@@ -0,0 +1,678 @@
+use crate::interpreter::normalize::normalize;
+use std::fmt;
+
+/// A long explanation of an error code, shown by `shikibu --explain`.
+#[derive(Debug)]
+pub struct Explanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// The options the wrong example has to be run with to cause the error.
+    pub flags: &'static [&'static str],
+    /// A program that causes the error.
+    pub wrong: &'static str,
+    /// The same program, corrected.
+    pub correct: &'static str,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.code, self.title)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.description)?;
+        writeln!(f)?;
+        match self.flags {
+            [] => writeln!(f, "まちがった例:")?,
+            flags => writeln!(
+                f,
+                "まちがった例（{} を付けて実行したとき）:",
+                flags.join(" ")
+            )?,
+        }
+        writeln!(f)?;
+        for line in self.wrong.lines() {
+            writeln!(f, "    {}", line)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "正しい例:")?;
+        writeln!(f)?;
+        for line in self.correct.lines() {
+            writeln!(f, "    {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the explanation of `code`, such as `R0005`. Lowercase and full-width
+/// letters and digits are accepted too.
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    let code = normalize(code).to_ascii_uppercase();
+    EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code == code)
+}
+
+pub const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "S0001",
+        title: "使えない文字があります",
+        description: "\
+プログラムの中に、式部では使えない記号が書かれています。
+「＠」や「＄」、「；」のような記号は、文字列（”〜”）の中やコメント（＃のあと）でなければ書けません。
+計算に使える記号は「＋」「ー」「＊」「／」「／／」「％」「＾」、比べるのに使える記号は「＝＝」「！＝」「＜」「＞」などです。",
+        flags: &[],
+        wrong: "表示（３＠４）",
+        correct: "表示（３＊４）",
+    },
+    Explanation {
+        code: "S0002",
+        title: "ここには書けないものがあります",
+        description: "\
+式や文の途中に、その場所には書けない記号やキーワードがあります。
+記号を二つ続けて書いてしまったり、式の途中で行を終えてしまったりすると起こります。
+エラーの位置の少し前から、書きまちがいがないか確かめてください。",
+        flags: &[],
+        wrong: "表示（１＋＊２）",
+        correct: "表示（１＋２）",
+    },
+    Explanation {
+        code: "S0003",
+        title: "必要な記号やキーワードがありません",
+        description: "\
+式部の文には決まった形があり、その形に必要な記号やキーワードが見つかりませんでした。
+たとえば「（」で始めたら「）」で閉じる必要があり、「もし」の条件のあとには「なら」が必要です。
+エラーメッセージには、必要なものと、かわりに見つかったものが書かれています。",
+        flags: &[],
+        wrong: "\
+ｘ＝１０
+もし　ｘ＞５
+　　表示（ｘ）",
+        correct: "\
+ｘ＝１０
+もし　ｘ＞５　なら
+　　表示（ｘ）",
+    },
+    Explanation {
+        code: "S0004",
+        title: "プログラムが途中で終わっています",
+        description: "\
+文が終わる前に、プログラムの最後に着きました。
+「もし〜なら」「くりかえし」「関数」のあとには、字下げしたブロックを書く必要があります。
+ブロックを書き忘れていないか、最後の行が途中で切れていないか確かめてください。",
+        flags: &[],
+        wrong: "\
+ｘ＝５
+もし　ｘ＞１　なら",
+        correct: "\
+ｘ＝５
+もし　ｘ＞１　なら
+　　表示（ｘ）",
+    },
+    Explanation {
+        code: "S0005",
+        title: "キーワードの書きまちがいです",
+        description: "\
+文の始まりに、キーワードによく似た名前が書かれています。
+式部のキーワードは「もし」「もしくは」「ちがえば」「くりかえし」「つぎへ」「ぬける」「かえす」「関数」「表示」などです。
+ひらがなの一文字ちがいに気をつけてください。",
+        flags: &[],
+        wrong: "\
+くりかえす
+　　表示（”こんにちは”）
+　　ぬける",
+        correct: "\
+くりかえし
+　　表示（”こんにちは”）
+　　ぬける",
+    },
+    Explanation {
+        code: "S0006",
+        title: "ブロックの中では関数を定義できません",
+        description: "\
+関数は、字下げしていない、いちばん外側でしか定義できません。
+「もし」や「くりかえし」、ほかの関数のブロックの中で「関数」と書くとこのエラーになります。
+関数を外側で定義して、ブロックの中からは呼び出すようにしてください。",
+        flags: &[],
+        wrong: "\
+もし　真　なら
+　　関数　あいさつ（）
+　　　　表示（”こんにちは”）",
+        correct: "\
+関数　あいさつ（）
+　　表示（”こんにちは”）
+
+もし　真　なら
+　　あいさつ（）",
+    },
+    Explanation {
+        code: "S0007",
+        title: "数値の書き方がまちがっています",
+        description: "\
+数値の書き方が正しくありません。
+数値は「１２３」「３．１４」のほか、「６．０２ｅ２３」のような指数、「０ｘＦＦ」（１６進数）、「０ｂ１０１」（２進数）、「０ｏ１７」（８進数）の形で書けます。
+桁の区切りの「＿」は数字と数字のあいだにだけ書けます。
+２進数には０と１、８進数には０から７の数字しか使えません。
+数字のすぐあとに英字を続けると、数値の一部とみなされてこのエラーになります。",
+        flags: &[],
+        wrong: "\
+人口＝１＿＿０００＿０００
+表示（０ｂ１０２）",
+        correct: "\
+人口＝１＿０００＿０００
+表示（０ｂ１０１）",
+    },
+    Explanation {
+        code: "S0008",
+        title: "文字列への式の埋め込みがまちがっています",
+        description: "\
+文字列の中の「｛」と「｝」のあいだには、値を埋め込む式を書きます。
+「｛」を閉じる「｝」は同じ行に書く必要があり、あいだを空にすることはできません。
+「｛」や「｝」を文字としてそのまま書きたいときは、「￥｛」「￥｝」と書いてください。",
+        flags: &[],
+        wrong: "\
+回数＝３
+表示（”回数：｛｝回”）",
+        correct: "\
+回数＝３
+表示（”回数：｛回数｝回”）",
+    },
+    Explanation {
+        code: "S0009",
+        title: "文字列の中で使えない「￥」の書き方です",
+        description: "\
+文字列の中の「￥」（または「\\」）は、そのあとの文字と組み合わせて特別な文字を表します。
+使えるのは「￥ｎ」（改行）、「￥ｔ」（タブ）、「￥ｒ」、「￥￥」（￥そのもの）、「￥”」（”そのもの）、「￥｛」「￥｝」と、「￥ｕ｛３０４２｝」のような文字の番号（１６進数）による指定です。
+それ以外の文字を「￥」のあとに書くとこのエラーになります。",
+        flags: &[],
+        wrong: "\
+表示（”値段は１００￥円です”）",
+        correct: "\
+表示（”値段は１００￥￥円です”）",
+    },
+    Explanation {
+        code: "S0010",
+        title: "文字列が閉じられていません",
+        description: "\
+文字列を始める「”」に対応する、終わりの「”」が見つかりませんでした。
+文字列の終わりに「”」を書いてください。
+「”””」や「『」で始めた文字列も、同じように「”””」や「』」で閉じる必要があります。
+文字列の中に「”」そのものを書きたいときは「￥”」と書きます。",
+        flags: &[],
+        wrong: "\
+表示（”こんにちは）",
+        correct: "\
+表示（”こんにちは”）",
+    },
+    Explanation {
+        code: "S0011",
+        title: "複数行の文字列の行が浅く字下げされています",
+        description: "\
+「”””」や「『』」で書いた複数行の文字列では、閉じる記号の前の字下げが、すべての行の先頭から取り除かれます。
+そのため、文字列の中の行は閉じる記号と同じかそれより深く字下げする必要があります。
+空の行はそのままで構いません。",
+        flags: &[],
+        wrong: "\
+表示（”””
+    一行目
+  二行目
+    ”””）",
+        correct: "\
+表示（”””
+    一行目
+      二行目
+    ”””）",
+    },
+    Explanation {
+        code: "S0012",
+        title: "コメントが閉じられていません",
+        description: "\
+「＃｛」で始めたコメントに対応する、終わりの「｝＃」が見つかりませんでした。
+このコメントは入れ子にできるので、中に「＃｛」を書いたときは、その分の「｝＃」も必要です。",
+        flags: &[],
+        wrong: "\
+＃｛ ここはコメント
+表示（１）",
+        correct: "\
+＃｛ ここはコメント ｝＃
+表示（１）",
+    },
+    Explanation {
+        code: "S0013",
+        title: "インデントが揃っていません",
+        description: "\
+ブロックの終わりで字下げを戻すときは、外側のどれかのブロックと同じ幅に揃える必要があります。
+字下げの幅は、半角スペースを１桁、全角スペースを２桁、タブを次の４の倍数の桁までとして数えます。
+全角スペースと半角スペースを混ぜて使うと、見た目が揃っていても幅がずれることがあります。
+数え方は --tab-width と --full-width-space で変えられます。",
+        flags: &[],
+        wrong: "\
+もし 真 なら
+    表示（１）
+  表示（２）",
+        correct: "\
+もし 真 なら
+    表示（１）
+    表示（２）",
+    },
+    Explanation {
+        code: "S0014",
+        title: "字下げに種類のちがう空白が混ざっています",
+        description: "\
+--no-mixed-indent を付けて実行すると、字下げには、ファイルの中で最初に使った種類の空白（半角スペース、全角スペース、タブのどれか）だけを使えます。
+見た目では区別しにくい空白が混ざるのを防ぐための決まりです。",
+        flags: &["--no-mixed-indent"],
+        wrong: "\
+もし 真 なら
+    表示（１）
+　　表示（２）",
+        correct: "\
+もし 真 なら
+    表示（１）
+    表示（２）",
+    },
+    Explanation {
+        code: "S0015",
+        title: "キーワードの辞書が見つかりません",
+        description: "\
+ファイルの先頭のコメントで「＃ キーワード：ひらがな」のように書くと、キーワードの書き方を選べます。
+使える辞書は、標準（standard）、ひらがな（hiragana）、英語（english）です。
+ひらがなの辞書では「真」を「しん」、「表示」を「ひょうじ」のように書き、英語の辞書では「もし」を「if」、「表示」を「print」のように書きます。",
+        flags: &[],
+        wrong: "\
+＃ キーワード：ひらかな
+ひょうじ（しん）",
+        correct: "\
+＃ キーワード：ひらがな
+ひょうじ（しん）",
+    },
+    Explanation {
+        code: "S0016",
+        title: "代入できないものに代入しています",
+        description: "\
+「＝」の左側に書けるのは、変数か、「列「１」」や「盤「１」「２」」のように変数に添字を付けたものだけです。
+関数の呼び出しや計算の結果には代入できません。
+値が等しいかを比べたいときは「＝＝」を使ってください。",
+        flags: &[],
+        wrong: "\
+ｘ＝１
+ｘ＋１＝２",
+        correct: "\
+ｘ＝１
+ｘ＝ｘ＋１",
+    },
+    Explanation {
+        code: "S0017",
+        title: "かっこが閉じられていません",
+        description: "\
+「（」「「」「［」で始めたかっこに対応する、閉じかっこが見つからないままプログラムが終わっています。
+かっこの中では行を分けて続きを書けるので、閉じかっこを書き忘れると、そのあとのすべての行がかっこの中として読まれてしまいます。
+エラーの位置にある開きかっこに対応する閉じかっこを書いてください。",
+        flags: &[],
+        wrong: "\
+関数　倍（ｘ
+    かえす　ｘ＊２",
+        correct: "\
+関数　倍（ｘ）
+    かえす　ｘ＊２",
+    },
+    Explanation {
+        code: "S0018",
+        title: "かっこが閉じられないまま次の文が始まっています",
+        description: "\
+「（」「「」「［」で始めたかっこが閉じられないまま、「もし」や「表示」のような新しい文が始まっています。
+かっこの中では行を分けて続きを書けますが、新しい文はかっこの中に書けません。
+閉じかっこを書き忘れていないか、エラーの位置にある開きかっこから確かめてください。",
+        flags: &[],
+        wrong: "\
+表示（１＋２
+表示（３）",
+        correct: "\
+表示（１＋２）
+表示（３）",
+    },
+    Explanation {
+        code: "R0001",
+        title: "ここでは実行できない文です",
+        description: "\
+その場所では実行できない文があります。
+たとえば計算の式だけを書いた行は、値をどうするのかが決まっていないので実行できません。
+値を見たいときは「表示（〜）」を、とっておきたいときは変数に代入してください。",
+        flags: &[],
+        wrong: "\
+ｘ＝１
+ｘ＋１",
+        correct: "\
+ｘ＝１
+表示（ｘ＋１）",
+    },
+    Explanation {
+        code: "R0002",
+        title: "足し算できない組み合わせです",
+        description: "\
+「＋」で足せるのは、数値どうしか、文字列を含む組み合わせだけです。
+文字列と数値を足すと、つなげた文字列になります。
+真偽値（真・偽）や無、配列は「＋」で足せません。",
+        flags: &[],
+        wrong: "表示（真＋１）",
+        correct: "表示（”答え：”＋１）",
+    },
+    Explanation {
+        code: "R0003",
+        title: "計算できない組み合わせです",
+        description: "\
+「ー」「＊」「／」「／／」「％」「＾」で計算できるのは数値どうしだけです。
+”１０”のように引用符で囲んだものは、数字が書かれていても文字列として扱われます。
+数値として計算したいときは、引用符を付けずに書いてください。",
+        flags: &[],
+        wrong: "表示（”１０”ー１）",
+        correct: "表示（１０ー１）",
+    },
+    Explanation {
+        code: "R0004",
+        title: "大きさを比べられない組み合わせです",
+        description: "\
+「＜」「＞」「＜＝」「＞＝」で大きさを比べられるのは数値どうしだけです。
+文字列や真偽値の大きさは比べられません。
+同じかどうかを調べるだけなら「＝＝」や「！＝」を使えば、どの値どうしでも比べられます。",
+        flags: &[],
+        wrong: "\
+もし　”３”＜５　なら
+　　表示（”小さい”）",
+        correct: "\
+もし　３＜５　なら
+　　表示（”小さい”）",
+    },
+    Explanation {
+        code: "R0005",
+        title: "変数が定義されていません",
+        description: "\
+まだ値を代入していない変数を使おうとしました。
+変数は「名前＝値」と代入してはじめて使えるようになります。
+名前の書きまちがいや、全角と半角、ひらがなとカタカナのちがいにも気をつけてください。
+関数の中からは、関数の外で代入した変数は使えません。引数として渡してください。",
+        flags: &[],
+        wrong: "\
+かいすう＝３
+表示（回数）",
+        correct: "\
+回数＝３
+表示（回数）",
+    },
+    Explanation {
+        code: "R0006",
+        title: "関数が定義されていません",
+        description: "\
+定義されていない関数を呼び出そうとしました。
+関数は「関数　名前（引数）」と定義してから呼び出します。
+名前の書きまちがいがないか確かめてください。",
+        flags: &[],
+        wrong: "\
+関数　二倍（ｎ）
+　　かえす　ｎ＊２
+
+表示（二ばい（３））",
+        correct: "\
+関数　二倍（ｎ）
+　　かえす　ｎ＊２
+
+表示（二倍（３））",
+    },
+    Explanation {
+        code: "R0007",
+        title: "同じ名前の関数がすでに定義されています",
+        description: "\
+同じ名前の関数を二回定義しました。
+関数の名前は、プログラムの中で一つずつちがうものにする必要があります。
+別の働きをする関数なら、別の名前を付けてください。",
+        flags: &[],
+        wrong: "\
+関数　計算（ｎ）
+　　かえす　ｎ＋１
+
+関数　計算（ｎ）
+　　かえす　ｎ＊２",
+        correct: "\
+関数　一足す（ｎ）
+　　かえす　ｎ＋１
+
+関数　二倍（ｎ）
+　　かえす　ｎ＊２",
+    },
+    Explanation {
+        code: "R0008",
+        title: "引数の数がちがいます",
+        description: "\
+関数を呼び出すときに渡した引数の数が、関数の定義にある引数の数と合っていません。
+関数の定義を見て、引数をいくつ、どの順番で渡すか確かめてください。",
+        flags: &[],
+        wrong: "\
+関数　足す（ａ、ｂ）
+　　かえす　ａ＋ｂ
+
+表示（足す（１））",
+        correct: "\
+関数　足す（ａ、ｂ）
+　　かえす　ａ＋ｂ
+
+表示（足す（１、２））",
+    },
+    Explanation {
+        code: "R0009",
+        title: "長さのない値です",
+        description: "\
+「長さ（〜）」で長さを調べられるのは、文字列と配列だけです。
+数値の桁数を知りたいときなどは、文字列にしてから調べてください。",
+        flags: &[],
+        wrong: "表示（長さ（１２３））",
+        correct: "表示（長さ（”１２３”））",
+    },
+    Explanation {
+        code: "R0010",
+        title: "配列の範囲外です",
+        description: "\
+配列の長さより大きい添字や、０より小さい添字を使っています。
+配列の添字は０から始まります。
+配列（３）で作った配列には、添字０から３まで使えます。",
+        flags: &[],
+        wrong: "\
+列＝配列（３）
+列「５」＝１",
+        correct: "\
+列＝配列（３）
+列「２」＝１",
+    },
+    Explanation {
+        code: "R0011",
+        title: "「！」を真偽値以外に使っています",
+        description: "\
+「！」は、真を偽に、偽を真にひっくり返す記号です。
+真偽値以外の値には使えません。
+数値が０でないかを調べたいときは「！＝」を使ってください。",
+        flags: &[],
+        wrong: "表示（！１）",
+        correct: "表示（１！＝０）",
+    },
+    Explanation {
+        code: "R0012",
+        title: "配列の添字が数値ではありません",
+        description: "\
+配列の何番目かを表す添字には、数値しか使えません。
+”１”のように引用符で囲むと文字列になってしまうので、引用符を付けずに書いてください。",
+        flags: &[],
+        wrong: "\
+列＝配列（３）
+列「”１”」＝５",
+        correct: "\
+列＝配列（３）
+列「１」＝５",
+    },
+    Explanation {
+        code: "R0013",
+        title: "配列ではない値に添字を付けています",
+        description: "\
+添字を付けて読んだり代入したりできるのは、配列だけです。
+先に「配列（長さ）」で配列を作って、変数に代入してください。
+配列の中に配列を入れると、「盤「１」「２」」のように添字を続けて書けます。",
+        flags: &[],
+        wrong: "\
+列＝０
+列「１」＝５",
+        correct: "\
+列＝配列（３）
+列「１」＝５",
+    },
+    Explanation {
+        code: "R0014",
+        title: "配列の長さが数値ではありません",
+        description: "\
+「配列（〜）」のかっこの中には、作る配列の長さを数値で書きます。
+文字列や真偽値を長さにすることはできません。",
+        flags: &[],
+        wrong: "列＝配列（”３”）",
+        correct: "列＝配列（３）",
+    },
+    Explanation {
+        code: "R0015",
+        title: "「くりかえし」の外で「ぬける」や「つぎへ」を使っています",
+        description: "\
+「ぬける」は「くりかえし」を終わらせる文、「つぎへ」は「くりかえし」の最初に戻る文です。
+どちらも「くりかえし」のブロックの中でしか意味がありません。
+「もし」のブロックの中でも、そのまわりに「くりかえし」がなければこのエラーになります。",
+        flags: &[],
+        wrong: "\
+ｎ＝２０
+もし　ｎ＞１０　なら
+　　ぬける",
+        correct: "\
+ｎ＝０
+くりかえし
+　　ｎ＝ｎ＋１
+　　もし　ｎ＞１０　なら
+　　　　ぬける",
+    },
+    Explanation {
+        code: "R0016",
+        title: "関数の外で「かえす」を使っています",
+        description: "\
+「かえす」は、関数の計算結果を呼び出したところへ返す文です。
+関数の中でしか使えません。
+プログラムの途中で値を見たいときは「表示（〜）」を使ってください。",
+        flags: &[],
+        wrong: "\
+ｘ＝１＋２
+かえす　ｘ",
+        correct: "\
+関数　足す（ａ、ｂ）
+　　かえす　ａ＋ｂ
+
+表示（足す（１、２））",
+    },
+    Explanation {
+        code: "R0017",
+        title: "０で割ろうとしています",
+        description: "\
+「／」「／／」で０で割ったり、「％」で０で割った余りを求めたりすることはできません。
+割る数が変数のときは、割る前に０でないことを「もし」で確かめてください。",
+        flags: &[],
+        wrong: "\
+ｘ＝０
+表示（１０／ｘ）",
+        correct: "\
+ｘ＝０
+もし　ｘ！＝０　なら
+　　表示（１０／ｘ）
+ちがえば
+　　表示（”０では割れません”）",
+    },
+    Explanation {
+        code: "R0018",
+        title: "「かつ」「または」を真偽値以外に使っています",
+        description: "\
+「かつ」と「または」の左右には、真か偽になる式しか書けません。
+左側だけで結果が決まるときは、右側は計算されません。「かつ」なら左側が偽のとき、「または」なら左側が真のときです。
+そのため「長さ（列）＞０　かつ　列「０」＝＝１」のように、右側を計算してよいかを左側で確かめられます。",
+        flags: &[],
+        wrong: "表示（１　かつ　真）",
+        correct: "表示（１！＝０　かつ　真）",
+    },
+    Explanation {
+        code: "R0019",
+        title: "関数の呼び出しが深くなりすぎました",
+        description: "\
+関数の中で関数を呼び出すと、呼び出しが重なっていきます。この重なりが1000回を超えると、このエラーで止まります。
+多くの場合、関数が自分自身を呼び出す再帰で、呼び出しをやめる場合が書かれていないか、その場合にたどりつかないことが原因です。
+「もし」で呼び出しをやめる場合を作り、呼び出すたびに引数がその場合に近づいているか確かめてください。",
+        flags: &[],
+        wrong: "\
+関数　階乗（ｎ）
+    かえす　ｎ＊階乗（ｎー１）
+表示（階乗（５））",
+        correct: "\
+関数　階乗（ｎ）
+    もし　ｎ＝＝０　なら
+        かえす　１
+    かえす　ｎ＊階乗（ｎー１）
+表示（階乗（５））",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cli::{parse_args, Command},
+        interpreter::{cst::Cst, tokens::dictionary::Dictionary, Interpreter, STACK_SIZE},
+    };
+
+    /// Runs `source` with the command line options `flags` the way `shikibu` does and
+    /// returns the code of the first error.
+    fn run(source: &str, flags: &[&str]) -> Option<&'static str> {
+        let args = flags.iter().copied().chain(["-e", source]);
+        let Ok(Command::Run(options)) = parse_args(args.map(String::from)) else {
+            panic!("the flags parse: {:?}", flags);
+        };
+        let dictionary = match Dictionary::select(source, None, options.keywords) {
+            Ok(dictionary) => dictionary,
+            Err(error) => return Some(error.code),
+        };
+        let cst = match Cst::parse(source, None, &options.indentation, dictionary) {
+            Ok(cst) => cst,
+            Err(error) => return Some(error.code),
+        };
+        let (ast, errors) = cst.lower();
+        if let Some(error) = errors.first() {
+            return Some(error.code);
+        }
+        Interpreter::run(ast, Vec::new(), dictionary)
+            .err()
+            .map(|error| error.code)
+    }
+
+    #[test]
+    fn examples_show_their_error() {
+        let check = || {
+            for explanation in EXPLANATIONS {
+                let code = explanation.code;
+                assert_eq!(
+                    run(explanation.wrong, explanation.flags),
+                    Some(code),
+                    "{}",
+                    code
+                );
+                assert_eq!(
+                    run(explanation.correct, explanation.flags),
+                    None,
+                    "{}",
+                    code
+                );
+            }
+        };
+        // The example of R0019 recurses as deep as the interpreter allows.
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(check)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt::{self, Write};
+
+/// A JSON value, written on a single line by its `Display` implementation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    /// Members are written in the order they are given.
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(values: Vec<T>) -> Self {
+        Json::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                f.write_char('[')?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(members) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Writes a string literal. Non-ASCII characters are written as they are, in UTF-8.
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_quotes_and_backslashes() {
+        let json = Json::from(r#"”a" \ b”"#);
+        assert_eq!(json.to_string(), r#""”a\" \\ b”""#);
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        let json = Json::from("改行\n復帰\rタブ\tベル\u{7}削除\u{7f}");
+        assert_eq!(
+            json.to_string(),
+            r#""改行\n復帰\rタブ\tベル\u0007削除\u007f""#
+        );
+    }
+
+    #[test]
+    fn escapes_object_keys_and_nested_values() {
+        let json = Json::Object(vec![
+            ("a\"b", Json::Array(vec!["\\".into(), Json::Null, 3.into()])),
+            ("c", None::<&str>.into()),
+        ]);
+        assert_eq!(json.to_string(), r#"{"a\"b":["\\",null,3],"c":null}"#);
+    }
+}
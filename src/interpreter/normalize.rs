@@ -0,0 +1,69 @@
+//! Normalization of identifiers and keywords, so that spellings which differ only
+//! in character width, such as `ＡＢＣ` and `ABC` or `ｶｳﾝﾄ` and `カウント`, mean the
+//! same name. This covers the part of NFKC that matters for Japanese text.
+
+/// The full-width forms of the half-width katakana and punctuation from `｡`
+/// (U+FF61) to `ﾟ` (U+FF9F), in code point order.
+const FULL_WIDTH_KANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Kana that take a voiced sound mark (゛), which is the next code point.
+const VOICED: &str =
+    "かきくけこさしすせそたちつてとはひふへほカキクケコサシスセソタチツテトハヒフヘホ";
+/// Kana that take a semi-voiced sound mark (゜), which is two code points after.
+const SEMI_VOICED: &str = "はひふへほハヒフヘホ";
+
+/// Returns the normalized form of `name`, which is used to compare names.
+/// Full-width ASCII becomes half-width, half-width katakana becomes full-width, and
+/// a sound mark written separately is combined with the kana before it.
+pub fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars().map(fold_width) {
+        let combined = match (normalized.chars().last(), c) {
+            (Some(base), '゛' | '\u{3099}') => voice(base),
+            (Some(base), '゜' | '\u{309A}') => semi_voice(base),
+            _ => None,
+        };
+        match combined {
+            Some(combined) => {
+                normalized.pop();
+                normalized.push(combined);
+            }
+            None => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Converts full-width ASCII characters such as `１`, `ｘ` and `＿` into half-width.
+pub fn half_width(c: char) -> char {
+    match c {
+        '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn fold_width(c: char) -> char {
+    match c {
+        '｡'..='ﾟ' => {
+            let index = c as usize - '｡' as usize;
+            FULL_WIDTH_KANA.chars().nth(index).unwrap_or(c)
+        }
+        _ => half_width(c),
+    }
+}
+
+fn voice(c: char) -> Option<char> {
+    match c {
+        'う' => Some('ゔ'),
+        'ウ' => Some('ヴ'),
+        c if VOICED.contains(c) => char::from_u32(c as u32 + 1),
+        _ => None,
+    }
+}
+
+fn semi_voice(c: char) -> Option<char> {
+    SEMI_VOICED
+        .contains(c)
+        .then(|| char::from_u32(c as u32 + 2))
+        .flatten()
+}
@@ -0,0 +1,95 @@
+use std::{fmt, rc::Rc};
+
+/// A point in the source code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Location {
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Column number in characters, starting from 1.
+    pub column: usize,
+    /// Offset from the beginning of the source in bytes.
+    pub byte: usize,
+    /// Offset from the beginning of the source in characters.
+    pub char: usize,
+}
+
+impl Location {
+    pub fn start() -> Self {
+        Self {
+            line: 1,
+            column: 1,
+            byte: 0,
+            char: 0,
+        }
+    }
+
+    /// Returns the location right after `c`, which must be the character at this location.
+    pub fn advance(&self, c: char) -> Self {
+        let (line, column) = match c {
+            '\n' => (self.line + 1, 1),
+            _ => (self.line, self.column + 1),
+        };
+        Self {
+            line,
+            column,
+            byte: self.byte + c.len_utf8(),
+            char: self.char + 1,
+        }
+    }
+}
+
+/// A range of the source code. `end` points right after the last character.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Span {
+    pub file: Option<Rc<str>>,
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span {
+    pub fn new(file: Option<Rc<str>>, start: Location, end: Location) -> Self {
+        Self { file, start, end }
+    }
+
+    /// Returns an empty span at the end of this span.
+    pub fn end_point(&self) -> Self {
+        Self::new(self.file.clone(), self.end, self.end)
+    }
+
+    /// Returns an empty span at the start of this span.
+    pub fn start_point(&self) -> Self {
+        Self::new(self.file.clone(), self.start, self.start)
+    }
+
+    /// Returns the span of `text`, which must be at the start of this span.
+    pub fn prefix(&self, text: &str) -> Self {
+        let end = text
+            .chars()
+            .fold(self.start, |location, c| location.advance(c));
+        Self::new(self.file.clone(), self.start, end)
+    }
+
+    /// Returns a span covering both `self` and `other`.
+    pub fn to(&self, other: &Span) -> Self {
+        let start = if other.start.char < self.start.char {
+            other.start
+        } else {
+            self.start
+        };
+        let end = if self.end.char < other.end.char {
+            other.end
+        } else {
+            self.end
+        };
+        Self::new(self.file.clone(), start, end)
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}", self.start.line, self.start.column)
+    }
+}
@@ -0,0 +1,149 @@
+/// Common words that are written either in kanji or in kana, such as `回数` and `かいすう`.
+/// Names are compared by their readings, so either spelling finds the other.
+const READINGS: &[(&str, &str)] = &[
+    ("回数", "かいすう"),
+    ("個数", "こすう"),
+    ("点数", "てんすう"),
+    ("引数", "ひきすう"),
+    ("関数", "かんすう"),
+    ("数字", "すうじ"),
+    ("数列", "すうれつ"),
+    ("数", "かず"),
+    ("合計", "ごうけい"),
+    ("総和", "そうわ"),
+    ("平均", "へいきん"),
+    ("結果", "けっか"),
+    ("答え", "こたえ"),
+    ("答", "こたえ"),
+    ("名前", "なまえ"),
+    ("値", "あたい"),
+    ("番号", "ばんごう"),
+    ("添字", "そえじ"),
+    ("位置", "いち"),
+    ("最大", "さいだい"),
+    ("最小", "さいしょう"),
+    ("長さ", "ながさ"),
+    ("始め", "はじめ"),
+    ("始", "はじめ"),
+    ("終わり", "おわり"),
+    ("終", "おわり"),
+    ("次", "つぎ"),
+    ("前", "まえ"),
+    ("後", "あと"),
+    ("余り", "あまり"),
+    ("文字列", "もじれつ"),
+    ("文字", "もじ"),
+    ("配列", "はいれつ"),
+    ("列", "れつ"),
+    ("行", "ぎょう"),
+    ("表", "ひょう"),
+    ("盤", "ばん"),
+    ("階乗", "かいじょう"),
+    ("年齢", "ねんれい"),
+    ("表示", "ひょうじ"),
+    ("真", "しん"),
+    ("偽", "ぎ"),
+    ("無", "む"),
+];
+
+/// Returns the candidate most similar to `name`, if any is similar enough to be a likely
+/// misspelling. Hiragana and katakana, full-width and half-width forms, and the kanji and
+/// kana spellings of the words in [`READINGS`] are treated as the same.
+pub fn similar<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let normalized = normalize(name);
+    let threshold = normalized.len() / 3;
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (distance(&normalized, &normalize(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Replaces known words with their readings and folds the differences of character forms.
+fn normalize(name: &str) -> Vec<char> {
+    let name = super::normalize::normalize(name);
+    let mut normalized = Vec::new();
+    let mut rest = name.as_str();
+    while let Some(c) = rest.chars().next() {
+        let word = READINGS
+            .iter()
+            .filter(|(word, _)| rest.starts_with(word))
+            .max_by_key(|(word, _)| word.len());
+        match word {
+            Some((word, reading)) => {
+                normalized.extend(reading.chars());
+                rest = &rest[word.len()..];
+            }
+            None => {
+                normalized.push(fold(c));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    normalized
+}
+
+/// Folds katakana into hiragana, and ASCII into lowercase.
+fn fold(c: char) -> char {
+    let c = match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    };
+    c.to_ascii_lowercase()
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent
+/// characters needed to turn `a` into `b`.
+fn distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance_between(a: &str, b: &str) -> usize {
+        distance(&normalize(a), &normalize(b))
+    }
+
+    #[test]
+    fn reads_words_in_kanji_and_kana_alike() {
+        assert_eq!(distance_between("回数", "かいすう"), 0);
+        assert_eq!(distance_between("回数", "カイスウ"), 0);
+        assert_eq!(distance_between("回数", "ｶｲｽｳ"), 0);
+        assert_eq!(distance_between("合計点数", "ごうけいてんすう"), 0);
+    }
+
+    #[test]
+    fn counts_edits_on_readings() {
+        assert_eq!(distance_between("回数", "かいすu"), 1);
+        assert_eq!(distance_between("最大値", "さいだいち"), 3);
+        assert_eq!(distance_between("ＡＢＣ", "bac"), 1);
+    }
+
+    #[test]
+    fn suggests_the_other_spelling() {
+        assert_eq!(similar("かいすう", ["回数", "個数"]), Some("回数"));
+        assert_eq!(similar("ごうけー", ["合計", "結果"]), Some("合計"));
+        assert_eq!(similar("値", ["番号"]), None);
+    }
+}
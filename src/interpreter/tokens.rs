@@ -1,30 +1,155 @@
-use self::{lexeme::Lexeme, sanitizer::sanitize, scanner::Scanner, token::Token};
-use std::{collections::VecDeque, str::FromStr};
+use self::{
+    dictionary::{Dictionary, STANDARD},
+    indentation::Indentation,
+    keyword::Keyword,
+    lexeme::Lexeme,
+    sanitizer::sanitize,
+    scanner::Scanner,
+    token::Token,
+};
+use super::error::SyntaxError;
+use super::span::Span;
+use std::{collections::VecDeque, rc::Rc, str::FromStr};
 
+pub mod dictionary;
+pub mod indentation;
 pub mod keyword;
 pub mod lexeme;
+mod numeral;
 mod sanitizer;
 mod scanner;
 pub mod symbol;
 pub mod token;
 
+/// Tokenizes `source` without dropping anything. The tokens cover the source
+/// without gaps, so their text, taken by the byte offsets of their spans, adds up to
+/// `source`. `file` is recorded in the span of every token.
+///
+/// Keywords are read in `dictionary`. A lexeme that cannot be read becomes an invalid
+/// token, and its error is returned along with the tokens.
+pub fn scan(
+    source: &str,
+    file: Option<&str>,
+    dictionary: &'static Dictionary,
+) -> (Vec<Token>, Vec<SyntaxError>) {
+    let mut scanner = Scanner::new(source, file.map(Rc::from), dictionary);
+    let tokens = scanner.by_ref().collect();
+    (tokens, scanner.into_errors())
+}
+
 #[derive(Debug)]
 pub struct Tokens {
     queue: VecDeque<Token>,
+    /// The span of the last token taken out of the queue, not counting the
+    /// layout tokens `Newline`, `Indent` and `Dedent`.
+    last_span: Span,
+    /// The dictionary the keywords were read in, which also spells them in errors.
+    dictionary: &'static Dictionary,
+    /// The errors found while scanning, which the parser reports.
+    errors: Vec<SyntaxError>,
+    /// The source the tokens were scanned from, which gives their text as written.
+    source: Rc<str>,
 }
 
 impl FromStr for Tokens {
-    type Err = ();
+    type Err = SyntaxError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let scanner = Scanner::new(s);
-        let mut q = scanner.collect();
-        let queue = sanitize(&mut q).unwrap();
-        Ok(Self { queue })
+        Self::new(s, None, &Indentation::default(), &STANDARD)
     }
 }
 
 impl Tokens {
+    /// Tokenizes `source`. `file` is recorded in the span of every token,
+    /// `indentation` says how the width of indentation is counted, and keywords are
+    /// read in `dictionary`.
+    pub fn new(
+        source: &str,
+        file: Option<&str>,
+        indentation: &Indentation,
+        dictionary: &'static Dictionary,
+    ) -> Result<Self, SyntaxError> {
+        let (tokens, errors) = scan(source, file, dictionary);
+        Self::from_scanned(source, tokens, errors, indentation, dictionary)
+    }
+
+    /// Arranges the tokens and errors returned by [`scan`] for the parser, dropping
+    /// spaces and comments and adding `Indent` and `Dedent` by the offside rule.
+    pub fn from_scanned(
+        source: &str,
+        tokens: Vec<Token>,
+        errors: Vec<SyntaxError>,
+        indentation: &Indentation,
+        dictionary: &'static Dictionary,
+    ) -> Result<Self, SyntaxError> {
+        let queue = sanitize(source, &mut VecDeque::from(tokens), indentation)?;
+        Ok(Self {
+            queue,
+            last_span: Span::default(),
+            dictionary,
+            errors,
+            source: Rc::from(source),
+        })
+    }
+
+    /// Wraps tokens that are already arranged for the parser, such as the tokens
+    /// taken out of a [`Cst`](super::cst::Cst), which were scanned from `source`.
+    pub fn arranged(
+        source: Rc<str>,
+        tokens: Vec<Token>,
+        errors: Vec<SyntaxError>,
+        dictionary: &'static Dictionary,
+    ) -> Self {
+        Self {
+            queue: VecDeque::from(tokens),
+            last_span: Span::default(),
+            dictionary,
+            errors,
+            source,
+        }
+    }
+
+    /// Wraps tokens scanned from the same source as `outer`, such as an expression
+    /// embedded in a string literal. `span` encloses all of them.
+    pub fn embedded(tokens: Vec<Token>, span: Span, outer: &Tokens) -> Self {
+        Self {
+            queue: VecDeque::from(tokens),
+            last_span: span,
+            dictionary: outer.dictionary,
+            errors: Vec::new(),
+            source: outer.source.clone(),
+        }
+    }
+
+    pub fn dictionary(&self) -> &'static Dictionary {
+        self.dictionary
+    }
+
+    /// Returns the text of `token` as it is written in the source.
+    pub fn text(&self, token: &Token) -> &str {
+        self.source
+            .get(token.span.start.byte..token.span.end.byte)
+            .unwrap_or_default()
+    }
+
+    /// Takes out the errors found while scanning, leaving none.
+    pub fn take_errors(&mut self) -> Vec<SyntaxError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Returns the span from `start` to the end of the last token taken out of the queue.
+    pub fn span_from(&self, start: &Span) -> Span {
+        start.to(&self.last_span)
+    }
+
+    /// Returns the span of the next token, or an empty span after the last one.
+    pub fn next_span(&self) -> Span {
+        match self.front() {
+            Some(token) => token.span.clone(),
+            None => self.last_span.end_point(),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.queue.len()
     }
@@ -34,7 +159,38 @@ impl Tokens {
     }
 
     pub fn shift(&mut self) -> Option<Token> {
-        self.queue.pop_front()
+        let token = self.queue.pop_front()?;
+        if !matches!(
+            token.lexeme,
+            Lexeme::Newline | Lexeme::Indent | Lexeme::Dedent
+        ) {
+            self.last_span = token.span.clone();
+        }
+        Some(token)
+    }
+
+    /// Takes out the next token, failing if there are no tokens left.
+    pub fn next_token(&mut self) -> Result<Token, SyntaxError> {
+        let span = self.next_span();
+        self.shift()
+            .ok_or_else(|| SyntaxError::unexpected_end_of_file(span))
+    }
+
+    /// Takes out the next token if it is `lexeme`, and fails otherwise.
+    pub fn expect(&mut self, lexeme: Lexeme) -> Result<(), SyntaxError> {
+        match self.front() {
+            Some(token) if token.lexeme == lexeme => {
+                _ = self.shift();
+                Ok(())
+            }
+            Some(token) => Err(SyntaxError::expected(
+                &lexeme,
+                token.clone(),
+                self.text(token),
+                self.dictionary,
+            )),
+            None => Err(SyntaxError::unexpected_end_of_file(self.next_span())),
+        }
     }
 
     pub fn get(&self, index: usize) -> Option<&Token> {
@@ -44,10 +200,39 @@ impl Tokens {
     pub fn consume(&mut self, lexeme: Lexeme) -> Result<(), ()> {
         if let Some(token) = self.front() {
             if token.lexeme == lexeme {
-                _ = self.queue.pop_front();
+                _ = self.shift();
                 return Ok(());
             }
         }
         Err(())
     }
+
+    /// Returns `true` if the last line must be followed by an indented block,
+    /// i.e. it is a `関数` header or ends with `なら`, `ちがえば`, `くりかえし`,
+    /// or a `もし` whose condition continues on the next line.
+    pub fn opens_block(&self) -> bool {
+        let line = self
+            .queue
+            .iter()
+            .map(|token| &token.lexeme)
+            .filter(|lexeme| {
+                !matches!(
+                    lexeme,
+                    Lexeme::Indent | Lexeme::Dedent | Lexeme::DocComment(_)
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut lines = line.split(|lexeme| lexeme == &&Lexeme::Newline);
+        let Some(last) = lines.rfind(|line| !line.is_empty()) else {
+            return false;
+        };
+
+        last.first() == Some(&&Lexeme::Keyword(Keyword::Function))
+            || matches!(
+                last.last(),
+                Some(Lexeme::Keyword(
+                    Keyword::Then | Keyword::Else | Keyword::Loop | Keyword::If | Keyword::Elif
+                ))
+            )
+    }
 }
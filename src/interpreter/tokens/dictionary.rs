@@ -0,0 +1,181 @@
+use super::keyword::Keyword;
+use crate::interpreter::{
+    error::SyntaxError,
+    normalize::normalize,
+    span::{Location, Span},
+};
+use std::{fmt, rc::Rc};
+
+/// A set of spellings for the keywords. A file selects one with a comment such as
+/// `＃ キーワード：ひらがな` above its first line of code.
+#[derive(Debug, PartialEq)]
+pub struct Dictionary {
+    pub name: &'static str,
+    /// Another name for the pragma and `--keywords`, in half-width letters.
+    pub alias: &'static str,
+    /// The spelling of each keyword, in the order of [`Keyword::ALL`].
+    spellings: [&'static str; 17],
+}
+
+pub static STANDARD: Dictionary = Dictionary {
+    name: "標準",
+    alias: "standard",
+    spellings: [
+        "もし",
+        "なら",
+        "もしくは",
+        "ちがえば",
+        "または",
+        "かつ",
+        "真",
+        "偽",
+        "無",
+        "関数",
+        "かえす",
+        "くりかえし",
+        "つぎへ",
+        "ぬける",
+        "表示",
+        "配列",
+        "長さ",
+    ],
+};
+
+/// Keywords without kanji, for children who have not learned them yet.
+pub static HIRAGANA: Dictionary = Dictionary {
+    name: "ひらがな",
+    alias: "hiragana",
+    spellings: [
+        "もし",
+        "なら",
+        "もしくは",
+        "ちがえば",
+        "または",
+        "かつ",
+        "しん",
+        "ぎ",
+        "む",
+        "かんすう",
+        "かえす",
+        "くりかえし",
+        "つぎへ",
+        "ぬける",
+        "ひょうじ",
+        "はいれつ",
+        "ながさ",
+    ],
+};
+
+/// Keywords spelled as in Python, for moving on to it.
+pub static ENGLISH: Dictionary = Dictionary {
+    name: "英語",
+    alias: "english",
+    spellings: [
+        "if", "then", "elif", "else", "or", "and", "True", "False", "None", "def", "return",
+        "while", "continue", "break", "print", "array", "len",
+    ],
+};
+
+impl Dictionary {
+    pub const ALL: [&'static Dictionary; 3] = [&STANDARD, &HIRAGANA, &ENGLISH];
+
+    /// Finds a dictionary by its name or alias.
+    pub fn named(name: &str) -> Option<&'static Dictionary> {
+        let name = normalize(name);
+        Self::ALL
+            .into_iter()
+            .find(|dictionary| dictionary.name == name || dictionary.alias == name)
+    }
+
+    /// Returns the dictionary `source` selects with a pragma, or `default` without one.
+    pub fn select(
+        source: &str,
+        file: Option<&str>,
+        default: &'static Dictionary,
+    ) -> Result<&'static Dictionary, SyntaxError> {
+        let Some((name, span)) = find_pragma(source, file.map(Rc::from)) else {
+            return Ok(default);
+        };
+        Self::named(&name).ok_or_else(|| SyntaxError::unknown_dictionary(&name, span))
+    }
+
+    pub fn keyword(&self, word: &str) -> Option<Keyword> {
+        let index = self
+            .spellings
+            .iter()
+            .position(|spelling| *spelling == word)?;
+        Some(Keyword::ALL[index].clone())
+    }
+
+    pub fn spelling(&self, keyword: &Keyword) -> &'static str {
+        let index = Keyword::ALL.iter().position(|k| k == keyword);
+        self.spellings[index.expect("every keyword is in Keyword::ALL")]
+    }
+}
+
+impl fmt::Display for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}（{}）", self.name, self.alias)
+    }
+}
+
+/// Looks for `＃ キーワード：(name)` among the comments and blank lines at the top
+/// of `source`, and returns the name with its span.
+fn find_pragma(source: &str, file: Option<Rc<str>>) -> Option<(String, Span)> {
+    let mut location = Location::start();
+    for line in source.split_inclusive('\n') {
+        let code = line.trim_start_matches([' ', '　', '\t']);
+        let comment = code.trim_start_matches(['#', '＃', '♯']);
+        if comment.len() == code.len() {
+            if code.trim().is_empty() {
+                location = line.chars().fold(location, |at, c| at.advance(c));
+                continue;
+            }
+            return None;
+        }
+        let pragma = comment.trim_start_matches([' ', '　']);
+        let name = ["キーワード：", "キーワード:"]
+            .iter()
+            .find_map(|prefix| pragma.strip_prefix(prefix));
+        if let Some(name) = name {
+            let name = name.trim_start_matches([' ', '　']);
+            let before = &line[..line.len() - name.len()];
+            let name = name.trim_end();
+            let start = before.chars().fold(location, |at, c| at.advance(c));
+            let end = name.chars().fold(start, |at, c| at.advance(c));
+            return Some((name.to_string(), Span::new(file, start, end)));
+        }
+        location = line.chars().fold(location, |at, c| at.advance(c));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_the_dictionary_of_the_pragma() {
+        let source = "＃ 説明\n\n＃ キーワード：英語\nprint(1)\n";
+        assert_eq!(
+            Dictionary::select(source, None, &HIRAGANA).ok(),
+            Some(&ENGLISH)
+        );
+    }
+
+    #[test]
+    fn keeps_the_default_without_a_pragma() {
+        let source = "表示（１）\n＃ キーワード：英語\n";
+        assert_eq!(
+            Dictionary::select(source, None, &HIRAGANA).ok(),
+            Some(&HIRAGANA)
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_dictionary() {
+        let source = "＃ キーワード：古文\n";
+        let error = Dictionary::select(source, None, &STANDARD).unwrap_err();
+        assert_eq!(error.code, "S0015");
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+
+/// How the width of indentation is counted, and whether different kinds of spaces
+/// may be used for it in one file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Indentation {
+    /// A tab advances to the next multiple of this many columns.
+    pub tab_width: usize,
+    /// The columns a full-width space `　` counts as, 1 or 2.
+    pub full_width_space: usize,
+    /// Whether half-width spaces, full-width spaces and tabs may be mixed.
+    pub allow_mixing: bool,
+}
+
+impl Default for Indentation {
+    fn default() -> Self {
+        Self {
+            tab_width: 4,
+            full_width_space: 2,
+            allow_mixing: true,
+        }
+    }
+}
+
+impl Indentation {
+    /// Returns the width of `spaces` in columns.
+    pub fn width(&self, spaces: &str) -> usize {
+        spaces.chars().fold(0, |width, c| match Space::from(c) {
+            Space::HalfWidth => width + 1,
+            Space::FullWidth => width + self.full_width_space,
+            Space::Tab => (width / self.tab_width + 1) * self.tab_width,
+        })
+    }
+}
+
+/// A kind of space that may be used for indentation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Space {
+    HalfWidth,
+    FullWidth,
+    Tab,
+}
+
+impl Space {
+    /// Returns the kinds of spaces in `spaces`, in the order they first appear.
+    pub fn kinds(spaces: &str) -> Vec<Space> {
+        let mut kinds = Vec::new();
+        for kind in spaces.chars().map(Space::from) {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+}
+
+impl From<char> for Space {
+    fn from(c: char) -> Self {
+        match c {
+            '　' => Space::FullWidth,
+            '\t' => Space::Tab,
+            _ => Space::HalfWidth,
+        }
+    }
+}
+
+impl fmt::Display for Space {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Space::HalfWidth => "半角スペース",
+            Space::FullWidth => "全角スペース",
+            Space::Tab => "タブ",
+        })
+    }
+}
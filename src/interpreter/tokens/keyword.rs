@@ -1,5 +1,8 @@
-use std::str::FromStr;
+use super::dictionary::STANDARD;
+use std::fmt;
 
+/// A keyword. How it is spelled depends on the [`Dictionary`](super::dictionary::Dictionary) the file uses; the
+/// comments show the standard spellings.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Keyword {
     /// もし
@@ -38,54 +41,50 @@ pub enum Keyword {
     Length,
 }
 
-impl FromStr for Keyword {
-    type Err = ();
+impl Keyword {
+    pub const ALL: [Keyword; 17] = [
+        Keyword::If,
+        Keyword::Then,
+        Keyword::Elif,
+        Keyword::Else,
+        Keyword::Or,
+        Keyword::And,
+        Keyword::True,
+        Keyword::False,
+        Keyword::Null,
+        Keyword::Function,
+        Keyword::Return,
+        Keyword::Loop,
+        Keyword::Continue,
+        Keyword::Break,
+        Keyword::Print,
+        Keyword::Array,
+        Keyword::Length,
+    ];
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "もし" => Ok(Keyword::If),
-            "なら" => Ok(Keyword::Then),
-            "もしくは" => Ok(Keyword::Elif),
-            "ちがえば" => Ok(Keyword::Else),
-            "または" => Ok(Keyword::Or),
-            "かつ" => Ok(Keyword::And),
-            "真" => Ok(Keyword::True),
-            "偽" => Ok(Keyword::False),
-            "無" => Ok(Keyword::Null),
-            "関数" => Ok(Keyword::Function),
-            "かえす" => Ok(Keyword::Return),
-            "くりかえし" => Ok(Keyword::Loop),
-            "つぎへ" => Ok(Keyword::Continue),
-            "ぬける" => Ok(Keyword::Break),
-            "表示" => Ok(Keyword::Print),
-            "配列" => Ok(Keyword::Array),
-            "長さ" => Ok(Keyword::Length),
-            _ => Err(()),
-        }
-    }
+    /// The keywords that are values, suggested in place of unknown variables.
+    pub const VALUES: [Keyword; 3] = [Keyword::True, Keyword::False, Keyword::Null];
+
+    /// The keywords that start a statement, which never continue an expression.
+    pub const STATEMENTS: [Keyword; 9] = [
+        Keyword::If,
+        Keyword::Elif,
+        Keyword::Else,
+        Keyword::Function,
+        Keyword::Return,
+        Keyword::Loop,
+        Keyword::Continue,
+        Keyword::Break,
+        Keyword::Print,
+    ];
+
+    /// The keywords that are called like functions.
+    pub const BUILTINS: [Keyword; 3] = [Keyword::Print, Keyword::Length, Keyword::Array];
 }
 
-impl ToString for Keyword {
-    fn to_string(&self) -> String {
-        match self {
-            Keyword::If => "もし",
-            Keyword::Then => "なら",
-            Keyword::Elif => "もしくは",
-            Keyword::Else => "ちがえば",
-            Keyword::Or => "または",
-            Keyword::And => "かつ",
-            Keyword::True => "真",
-            Keyword::False => "偽",
-            Keyword::Null => "無",
-            Keyword::Function => "関数",
-            Keyword::Return => "かえす",
-            Keyword::Loop => "くりかえし",
-            Keyword::Continue => "つぎへ",
-            Keyword::Break => "ぬける",
-            Keyword::Print => "表示",
-            Keyword::Array => "配列",
-            Keyword::Length => "長さ",
-        }
-        .to_string()
+impl fmt::Display for Keyword {
+    /// Spells the keyword in the standard [`Dictionary`](super::dictionary::Dictionary).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(STANDARD.spelling(self))
     }
 }
@@ -1,4 +1,6 @@
-use super::{keyword::Keyword, symbol::Symbol};
+use super::{keyword::Keyword, symbol::Symbol, token::Token};
+use crate::interpreter::span::Span;
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Lexeme {
@@ -6,26 +8,59 @@ pub enum Lexeme {
     Keyword(Keyword),
     Identifier(String),
     String(String),
+    /// A string literal with embedded expressions, such as `”回数：｛回数｝”`.
+    Template(Vec<TemplatePart>),
     Number(String),
-    Spaces(usize),
+    /// Spaces and tabs as they are written.
+    Spaces(String),
     Newline,
+    /// `￥` at the end of a line, which continues the line on the next one.
+    Continuation,
     Comment,
+    /// The text of a `＃＃` comment. The sanitizer joins the comments on the lines
+    /// above a statement into one token, placed right before the statement.
+    DocComment(String),
     Indent,
     Dedent,
-    Invalid(char),
+    /// Text that cannot be read, such as the symbol `＠`, the number `0b102` or a string
+    /// without its closing `”`. Its error is reported along with the syntax errors.
+    Invalid(String),
 }
 
-impl ToString for Lexeme {
-    fn to_string(&self) -> String {
+impl fmt::Display for Lexeme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Lexeme::Symbol(symbol) => symbol.to_string(),
-            Lexeme::Keyword(keyword) => keyword.to_string(),
-            Lexeme::Identifier(identifier) => identifier.to_string(),
-            Lexeme::String(string) => string.to_string(),
-            Lexeme::Number(number) => number.to_string(),
-            Lexeme::Spaces(len) => " ".repeat(*len),
-            Lexeme::Invalid(c) => c.to_string(),
-            _ => "".to_string(),
+            Lexeme::Symbol(symbol) => write!(f, "{}", symbol),
+            Lexeme::Keyword(keyword) => write!(f, "{}", keyword),
+            Lexeme::Identifier(identifier) => f.write_str(identifier),
+            Lexeme::String(string) => f.write_str(string),
+            Lexeme::Template(parts) => {
+                for part in parts {
+                    match part {
+                        TemplatePart::Text(text) => f.write_str(text)?,
+                        TemplatePart::Expression(tokens, _) => {
+                            f.write_str("｛")?;
+                            for token in tokens {
+                                write!(f, "{}", token.lexeme)?;
+                            }
+                            f.write_str("｝")?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Lexeme::Number(number) => f.write_str(number),
+            Lexeme::Spaces(spaces) => f.write_str(spaces),
+            Lexeme::Continuation => f.write_str("￥"),
+            Lexeme::Invalid(text) => write!(f, "{}", text),
+            _ => Ok(()),
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Text(String),
+    /// The tokens between `｛` and `｝`, and the span including the braces.
+    Expression(Vec<Token>, Span),
+}
@@ -0,0 +1,166 @@
+//! Helpers for number literals, including kanji numerals (漢数字) such as `百二十三`,
+//! `二〇二四` and `三・一四`.
+
+/// The separator between the integer part and the fraction part, as in `三・一四`.
+pub const KANJI_DECIMAL_POINT: char = '・';
+
+pub fn is_kanji_digit(c: char) -> bool {
+    kanji_digit(c).is_some()
+}
+
+pub fn is_kanji_numeral(c: char) -> bool {
+    is_kanji_digit(c) || small_unit(c).is_some() || large_unit(c).is_some()
+}
+
+fn kanji_digit(c: char) -> Option<u64> {
+    match c {
+        '〇' | '零' => Some(0),
+        '一' => Some(1),
+        '二' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    }
+}
+
+fn small_unit(c: char) -> Option<u64> {
+    match c {
+        '十' => Some(10),
+        '百' => Some(100),
+        '千' => Some(1000),
+        _ => None,
+    }
+}
+
+fn large_unit(c: char) -> Option<u64> {
+    match c {
+        '万' => Some(10_000),
+        '億' => Some(100_000_000),
+        '兆' => Some(1_000_000_000_000),
+        _ => None,
+    }
+}
+
+/// Converts the integer part of a kanji numeral into decimal digits.
+///
+/// Both positional forms (`三千五百`, `二十一万`) and digit by digit forms (`二〇二四`)
+/// are accepted, and a digit by digit form may stand before a large unit (`二〇万`).
+/// Units must appear in decreasing order, and the part below a large unit is written in
+/// one form only, so malformed runs such as `十十`, `百千`, `二〇十` or `十二三` are
+/// rejected.
+pub fn parse_kanji_integer(chars: &[char]) -> Option<String> {
+    let mut total: u64 = 0;
+    // The value below the current large unit, e.g. `二千三百` in `一万二千三百`.
+    let mut section: u64 = 0;
+    // The digits written since the last unit, and how many there are.
+    let mut digits: Option<u64> = None;
+    let mut run = 0;
+    let mut last_small_unit = u64::MAX;
+    let mut last_large_unit = u64::MAX;
+
+    for &c in chars {
+        if let Some(digit) = kanji_digit(c) {
+            digits = Some(digits.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+            run += 1;
+        } else if let Some(unit) = small_unit(c) {
+            if unit >= last_small_unit || run > 1 {
+                return None;
+            }
+            last_small_unit = unit;
+            section = section.checked_add(digits.take().unwrap_or(1).checked_mul(unit)?)?;
+            run = 0;
+        } else if let Some(unit) = large_unit(c) {
+            if unit >= last_large_unit || (run > 1 && last_small_unit != u64::MAX) {
+                return None;
+            }
+            // A large unit without a number before it, as in `万`, counts one of it.
+            let value = match digits.take() {
+                None if last_small_unit == u64::MAX => 1,
+                digits => section.checked_add(digits.unwrap_or(0))?,
+            };
+            last_large_unit = unit;
+            last_small_unit = u64::MAX;
+            total = total.checked_add(value.checked_mul(unit)?)?;
+            section = 0;
+            run = 0;
+        } else {
+            return None;
+        }
+    }
+    if chars.is_empty() || (run > 1 && last_small_unit != u64::MAX) {
+        return None;
+    }
+    let value = total
+        .checked_add(section)?
+        .checked_add(digits.unwrap_or(0))?;
+    Some(value.to_string())
+}
+
+/// Converts the fraction part of a kanji numeral, the digits after `・`.
+pub fn parse_kanji_fraction(chars: &[char]) -> Option<String> {
+    if chars.is_empty() {
+        return None;
+    }
+    chars
+        .iter()
+        .map(|&c| kanji_digit(c).and_then(|digit| char::from_digit(digit as u32, 10)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(numeral: &str) -> Option<String> {
+        parse_kanji_integer(&numeral.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_positional_numerals() {
+        assert_eq!(parse("百二十三").as_deref(), Some("123"));
+        assert_eq!(parse("三千五百").as_deref(), Some("3500"));
+        assert_eq!(parse("二十一万").as_deref(), Some("210000"));
+        assert_eq!(parse("一億二千万三").as_deref(), Some("120000003"));
+        assert_eq!(parse("万").as_deref(), Some("10000"));
+    }
+
+    #[test]
+    fn parses_digit_by_digit_numerals() {
+        assert_eq!(parse("二〇二四").as_deref(), Some("2024"));
+        assert_eq!(parse("二〇万").as_deref(), Some("200000"));
+        assert_eq!(parse("一万二〇〇").as_deref(), Some("10200"));
+    }
+
+    #[test]
+    fn counts_zero_of_a_large_unit_as_zero() {
+        assert_eq!(parse("〇万").as_deref(), Some("0"));
+        assert_eq!(parse("零万").as_deref(), Some("0"));
+        assert_eq!(parse("一億〇万五").as_deref(), Some("100000005"));
+    }
+
+    #[test]
+    fn rejects_digit_runs_mixed_with_small_units() {
+        assert_eq!(parse("二〇十"), None);
+        assert_eq!(parse("十二三"), None);
+        assert_eq!(parse("二十三四万"), None);
+    }
+
+    #[test]
+    fn rejects_units_out_of_order() {
+        assert_eq!(parse("十十"), None);
+        assert_eq!(parse("百千"), None);
+        assert_eq!(parse("万億"), None);
+    }
+
+    #[test]
+    fn parses_fractions() {
+        let fraction = |numeral: &str| parse_kanji_fraction(&numeral.chars().collect::<Vec<_>>());
+        assert_eq!(fraction("一四").as_deref(), Some("14"));
+        assert_eq!(fraction("十"), None);
+    }
+}
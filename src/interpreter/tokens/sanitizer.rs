@@ -1,47 +1,139 @@
-use super::{lexeme::Lexeme, token::Token};
-use crate::interpreter::error::SyntaxError;
+use super::{
+    indentation::{Indentation, Space},
+    keyword::Keyword,
+    lexeme::Lexeme,
+    symbol::Symbol,
+    token::Token,
+};
+use crate::interpreter::{error::SyntaxError, span::Span};
 use std::collections::VecDeque;
 
-pub fn sanitize(queue: &mut VecDeque<Token>) -> Result<VecDeque<Token>, ()> {
+/// Drops spaces and comments from the tokens scanned from `source`, and adds `Indent`
+/// and `Dedent` by the offside rule. Lines are joined inside brackets and after `￥`,
+/// and a bracket left open at the end of the source, or when a statement starts on a
+/// line that is not indented into it, is an error.
+pub fn sanitize(
+    source: &str,
+    queue: &mut VecDeque<Token>,
+    indentation: &Indentation,
+) -> Result<VecDeque<Token>, SyntaxError> {
     let mut sanitized = VecDeque::new();
     let mut indents = Indents::from(0);
-    cosume_empty_lines(queue).ok();
+    let mut style = Style::default();
+    let mut docs = Vec::new();
 
     // Add a newline token to the end of the queue to ensure that the last line is processed.
-    if let Some(_) = queue.back() {
-        queue.push_back(Token::newline());
+    if let Some(last) = queue.back() {
+        queue.push_back(Token::newline(last.span.end_point()));
     } else {
         return Ok(sanitized);
     }
+    cosume_empty_lines(queue, &mut docs).ok();
+    // Documentation comments with no statement after them document nothing.
+    if queue.is_empty() {
+        return Ok(sanitized);
+    }
+    sanitized.extend(join_doc_comments(&mut docs));
+
+    let text = |token: &Token| &source[token.span.start.byte..token.span.end.byte];
+    // The `（`, `「` and `［` not closed yet, the innermost last. Lines are joined while
+    // any of them is open.
+    let mut brackets: Vec<Token> = Vec::new();
+    // Whether an invalid token, such as a string left open, is inside the brackets.
+    // Its error is reported instead, since the closing bracket may be in it.
+    let mut invalid_inside = false;
+    // Whether the line ends with `￥`, which joins it to the next line.
+    let mut continued = false;
 
     while let Some(token) = queue.front() {
         match token.lexeme {
+            Lexeme::Newline if !brackets.is_empty() || continued => {
+                _ = queue.pop_front();
+                continued = false;
+                // The indentation of a continued line has no meaning, but a statement
+                // that starts no deeper than the block means a bracket was left open.
+                let mut width = 0;
+                while let Some(token) = queue.front() {
+                    match &token.lexeme {
+                        Lexeme::Spaces(spaces) => width = indentation.width(spaces),
+                        Lexeme::Comment | Lexeme::DocComment(_) => {}
+                        Lexeme::Newline if !brackets.is_empty() => width = 0,
+                        _ => break,
+                    }
+                    _ = queue.pop_front();
+                }
+                if let (Some(open), Some(front), false) =
+                    (brackets.last(), queue.front(), invalid_inside)
+                {
+                    if let Lexeme::Keyword(keyword) = &front.lexeme {
+                        if Keyword::STATEMENTS.contains(keyword)
+                            && indents.last().is_some_and(|last| width <= *last)
+                        {
+                            return Err(SyntaxError::unclosed_bracket_before(
+                                text(open),
+                                text(front),
+                                front.span.start.line,
+                                open.span.clone(),
+                            ));
+                        }
+                    }
+                }
+            }
             Lexeme::Newline => {
-                if cosume_empty_lines(queue).is_ok() {
-                    sanitized.push_back(Token::newline());
+                let span = token.span.clone();
+                if cosume_empty_lines(queue, &mut docs).is_ok() {
+                    sanitized.push_back(Token::newline(span));
                 }
                 let Some(front) = queue.front() else {
                     break;
                 };
-                let mut offside_tokens = generate_offside_tokens(front, &mut indents).unwrap();
+                let mut offside_tokens =
+                    generate_offside_tokens(front, &mut indents, indentation, &mut style)?;
 
-                while !offside_tokens.is_empty() {
-                    sanitized.push_back(offside_tokens.pop().unwrap());
+                while let Some(offside_token) = offside_tokens.pop() {
+                    sanitized.push_back(offside_token);
                 }
+                sanitized.extend(join_doc_comments(&mut docs));
             }
-            Lexeme::Comment => _ = queue.pop_front(),
+            Lexeme::Comment | Lexeme::DocComment(_) => _ = queue.pop_front(),
             Lexeme::Spaces(_) => _ = queue.pop_front(),
-            _ => sanitized.push_back(queue.pop_front().unwrap()),
+            Lexeme::Continuation => {
+                continued = true;
+                _ = queue.pop_front();
+            }
+            _ => {
+                match token.lexeme {
+                    Lexeme::Symbol(Symbol::OpenParen | Symbol::OpenBracket) => {
+                        brackets.push(token.clone());
+                    }
+                    Lexeme::Symbol(Symbol::CloseParen | Symbol::CloseBracket) => {
+                        _ = brackets.pop();
+                        invalid_inside &= !brackets.is_empty();
+                    }
+                    Lexeme::Invalid(_) => invalid_inside |= !brackets.is_empty(),
+                    _ => (),
+                }
+                sanitized.push_back(queue.pop_front().unwrap());
+            }
         }
     }
+    if let (Some(open), false) = (brackets.last(), invalid_inside) {
+        return Err(SyntaxError::unclosed_bracket(text(open), open.span.clone()));
+    }
+    let end = sanitized
+        .back()
+        .map(|token| token.span.end_point())
+        .unwrap_or_default();
     while !indents.is_top_level() {
-        sanitized.push_back(Token::dedent());
+        sanitized.push_back(Token::dedent(end.clone()));
         _ = indents.pop();
     }
     Ok(sanitized)
 }
 
-fn cosume_empty_lines(queue: &mut VecDeque<Token>) -> Result<(), ()> {
+/// Removes the empty lines at the front of `queue`, keeping the documentation
+/// comments on them in `docs`.
+fn cosume_empty_lines(queue: &mut VecDeque<Token>, docs: &mut Vec<Token>) -> Result<(), ()> {
     let mut deletable: Vec<&Token> = Vec::new();
 
     for index in 0..queue.len() {
@@ -51,6 +143,7 @@ fn cosume_empty_lines(queue: &mut VecDeque<Token>) -> Result<(), ()> {
             Lexeme::Spaces(_) => deletable.push(token),
             Lexeme::Newline => deletable.push(token),
             Lexeme::Comment => deletable.push(token),
+            Lexeme::DocComment(_) => deletable.push(token),
             _ => break,
         }
     }
@@ -58,59 +151,125 @@ fn cosume_empty_lines(queue: &mut VecDeque<Token>) -> Result<(), ()> {
 
     for index in 0..deletable.len() {
         if let Lexeme::Newline = deletable.get(index).unwrap().lexeme {
-            queue.drain(..deletable.len() - index);
+            let lines = queue.drain(..deletable.len() - index);
+            docs.extend(lines.filter(|token| matches!(token.lexeme, Lexeme::DocComment(_))));
             return Ok(());
         }
     }
     Err(())
 }
 
+/// Joins the documentation comments found on the empty lines above a statement
+/// into one token.
+fn join_doc_comments(docs: &mut Vec<Token>) -> Option<Token> {
+    let first = docs.first()?.span.clone();
+    let span = first.to(&docs.last()?.span);
+    let doc = docs
+        .drain(..)
+        .filter_map(|token| match token.lexeme {
+            Lexeme::DocComment(line) => Some(line),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(Token::doc_comment(doc, span))
+}
+
 fn generate_offside_tokens(
     front: &Token,
     indents: &mut Indents,
+    indentation: &Indentation,
+    style: &mut Style,
 ) -> Result<Vec<Token>, SyntaxError> {
     let mut offside_tokens: Vec<Token> = Vec::new();
 
     // If the next token is not a space, it is assumed that there is no indent,
     // and all indentations are removed.
-    let Lexeme::Spaces(spaces) = front.lexeme else {
+    let Lexeme::Spaces(spaces) = &front.lexeme else {
         while indents.len() != 1 {
             _ = indents.pop();
-            offside_tokens.push(Token::dedent());
+            offside_tokens.push(Token::dedent(front.span.start_point()));
         }
+        style.previous.clear();
         return Ok(offside_tokens);
     };
 
-    if indents.last().unwrap() < &spaces {
+    if !indentation.allow_mixing {
+        style.check_mixing(spaces, &front.span)?;
+    }
+    let width = indentation.width(spaces);
+
+    if indents.last().unwrap() < &width {
         // Adds an indent token if the indent width is larger than the current width.
-        indents.push(&spaces);
-        offside_tokens.push(Token::indent());
-    } else if &spaces < indents.last().unwrap() {
+        indents.push(&width);
+        offside_tokens.push(Token::indent(front.span.clone()));
+    } else if &width < indents.last().unwrap() {
         // If the indent width is less than the current one,
         // pop from the top of the indent stack until the same width is found,
         // then add as many dedentation tokens as you pop.
-        loop {
-            // Raise an error if an indent of a width that does not exist on the indent stack is detected.
-            let Some(indent_length) = indents.last() else {
-                return Err(SyntaxError::unexpected_token(front.clone()));
-            };
-            if &spaces == indent_length {
-                break;
-            };
+        if !indents.0.contains(&width) {
+            let mut error =
+                SyntaxError::inconsistent_indentation(&indents.0, width, front.span.clone());
+            let (previous, current) = (Space::kinds(&style.previous), Space::kinds(spaces));
+            if !previous.is_empty() && previous != current {
+                error = error.with_hint(format!(
+                    "前の行は{}、この行は{}で字下げされています",
+                    join(&previous),
+                    join(&current)
+                ));
+            }
+            return Err(error);
+        }
+        while indents.last() != Some(&width) {
             _ = indents.pop();
-            offside_tokens.push(Token::dedent());
+            offside_tokens.push(Token::dedent(front.span.end_point()));
         }
     }
+    style.previous.clone_from(spaces);
     Ok(offside_tokens)
 }
 
+fn join(kinds: &[Space]) -> String {
+    let kinds = kinds.iter().map(Space::to_string).collect::<Vec<_>>();
+    kinds.join("と")
+}
+
+/// What the sanitizer remembers about the indentation of the lines so far.
+#[derive(Default)]
+struct Style {
+    /// The first kind of space used for indentation, and where it is used.
+    first: Option<(Space, Span)>,
+    /// The indentation of the previous line.
+    previous: String,
+}
+
+impl Style {
+    /// Fails if `spaces` uses a kind of space other than the first one in the file.
+    fn check_mixing(&mut self, spaces: &str, span: &Span) -> Result<(), SyntaxError> {
+        for kind in Space::kinds(spaces) {
+            match &self.first {
+                None => self.first = Some((kind, span.clone())),
+                Some((first, first_span)) if *first != kind => {
+                    let line = first_span.start.line;
+                    return Err(SyntaxError::mixed_indentation(
+                        kind,
+                        *first,
+                        line,
+                        span.clone(),
+                    ));
+                }
+                Some(_) => (),
+            }
+        }
+        Ok(())
+    }
+}
+
 struct Indents(Vec<usize>);
 
 impl From<usize> for Indents {
     fn from(len: usize) -> Self {
-        let mut indents = Vec::new();
-        indents.push(len);
-        Self(indents)
+        Self(vec![len])
     }
 }
 
@@ -135,3 +294,187 @@ impl Indents {
         self.0.last().unwrap() == &0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokens::{dictionary::STANDARD, scan};
+
+    fn lexemes(source: &str) -> Result<Vec<Lexeme>, SyntaxError> {
+        lexemes_with(source, &Indentation::default())
+    }
+
+    fn lexemes_with(source: &str, indentation: &Indentation) -> Result<Vec<Lexeme>, SyntaxError> {
+        let (tokens, _) = scan(source, None, &STANDARD);
+        let tokens = sanitize(source, &mut VecDeque::from(tokens), indentation)?;
+        Ok(tokens.into_iter().map(|token| token.lexeme).collect())
+    }
+
+    #[test]
+    fn joins_lines_inside_brackets() {
+        let joined = lexemes("ｘ ＝ ［１、\n    ２］\n表示（ｘ「０」、\n\n  ｘ）\n").unwrap();
+        let newlines = joined
+            .iter()
+            .filter(|lexeme| **lexeme == Lexeme::Newline)
+            .count();
+        assert_eq!(newlines, 2);
+        assert!(!joined.contains(&Lexeme::Indent));
+    }
+
+    #[test]
+    fn joins_a_line_ending_with_a_continuation() {
+        assert_eq!(
+            lexemes("ｘ ＝ １ ￥\n    ＋ ２\n").unwrap(),
+            [
+                Lexeme::Identifier("ｘ".to_string()),
+                Lexeme::Symbol(Symbol::Equal),
+                Lexeme::Number("1".to_string()),
+                Lexeme::Symbol(Symbol::Plus),
+                Lexeme::Number("2".to_string()),
+                Lexeme::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_a_bracket_left_open_at_the_end() {
+        for (source, column, hint) in [
+            ("関数　ｆ（ｘ\n    かえす ｘ\n", 5, "「）」"),
+            ("ｘ ＝ 列「１\n", 6, "「」」"),
+            ("ｘ ＝ ［１、\n２\n", 5, "「］」"),
+        ] {
+            let error = lexemes(source).expect_err("a bracket is left open");
+            assert_eq!(error.code, "S0017", "{}", source);
+            assert_eq!(error.span.unwrap().start.column, column, "{}", source);
+            assert!(error.hints[0].contains(hint), "{}", source);
+        }
+    }
+
+    #[test]
+    fn reports_a_statement_that_starts_inside_an_open_bracket() {
+        let error = lexemes("表示（ｆ（１）\nもし 真 なら\n    表示（２））\n")
+            .expect_err("a bracket is left open");
+        assert_eq!(error.code, "S0018");
+        assert_eq!(error.span.unwrap().start.column, 3);
+        assert_eq!(error.notes, ["2行目の「もし」から新しい文が始まっています"]);
+    }
+
+    #[test]
+    fn leaves_an_open_bracket_with_an_invalid_token_to_its_error() {
+        assert!(lexemes("表示（”あいう\nもし 真 なら\n    表示（２）\n").is_ok());
+    }
+
+    #[test]
+    fn drops_doc_comments_that_document_nothing() {
+        for source in [
+            "＃＃ 説明だけ",
+            "＃＃ 説明だけ\n",
+            "＃ コメント",
+            "＃｛ ａ ｝＃",
+        ] {
+            assert_eq!(lexemes(source).unwrap(), [], "{}", source);
+        }
+        assert_eq!(
+            lexemes("表示（１）\n＃＃ 説明").unwrap(),
+            [
+                Lexeme::Keyword(Keyword::Print),
+                Lexeme::Symbol(Symbol::OpenParen),
+                Lexeme::Number("1".to_string()),
+                Lexeme::Symbol(Symbol::CloseParen),
+                Lexeme::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_the_widths_an_inconsistent_line_could_have() {
+        let error = lexemes("もし 真 なら\n    もし 真 なら\n        表示（１）\n  表示（２）\n")
+            .expect_err("the last line is not aligned");
+        assert_eq!(error.code, "S0013");
+        assert_eq!(
+            error.notes,
+            ["字下げの幅は0桁、4桁、8桁のどれかのはずですが、2桁になっています"]
+        );
+    }
+
+    #[test]
+    fn reports_mixed_spaces_only_when_they_are_not_allowed() {
+        let source = "もし 真 なら\n    表示（１）\nもし 真 なら\n　　表示（２）\n";
+        assert!(lexemes(source).is_ok());
+
+        let strict = Indentation {
+            allow_mixing: false,
+            ..Indentation::default()
+        };
+        let error = lexemes_with(source, &strict).expect_err("the spaces are mixed");
+        assert_eq!(error.code, "S0014");
+        assert_eq!(error.span.unwrap().start.line, 4);
+        assert_eq!(
+            error.notes,
+            ["このファイルでは2行目から半角スペースで字下げしています"]
+        );
+        assert_eq!(error.hints, ["字下げには半角スペースだけを使ってください"]);
+    }
+
+    #[test]
+    fn counts_tabs_to_the_next_tab_stop() {
+        for (tab_width, tabs, spaces) in [
+            (4, "\t", "    "),
+            (4, "  \t", "    "),
+            (4, "    \t", "        "),
+            (2, " \t", "  "),
+            (2, "  \t", "    "),
+            (8, "\t", "        "),
+        ] {
+            let indentation = Indentation {
+                tab_width,
+                ..Indentation::default()
+            };
+            let source = format!("もし 真 なら\n{}表示（１）\n{}表示（２）\n", tabs, spaces);
+            let indents = lexemes_with(&source, &indentation)
+                .unwrap()
+                .into_iter()
+                .filter(|lexeme| *lexeme == Lexeme::Indent)
+                .count();
+            assert_eq!(indents, 1, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn counts_full_width_spaces_as_configured() {
+        let source = "もし 真 なら\n  表示（１）\n　表示（２）\n";
+        assert!(lexemes(source).is_ok());
+
+        let narrow = Indentation {
+            full_width_space: 1,
+            ..Indentation::default()
+        };
+        let error = lexemes_with(source, &narrow).expect_err("the widths differ");
+        assert_eq!(error.code, "S0013");
+        assert_eq!(
+            error.notes,
+            ["字下げの幅は0桁、2桁のどれかのはずですが、1桁になっています"]
+        );
+    }
+
+    #[test]
+    fn keeps_the_lines_of_a_multiline_string_inside_it() {
+        for source in [
+            "表示（”””\n    ａ\n  ｂ\n  ”””）\n",
+            "表示（『\n    ａ\n  ｂ\n  』）\n",
+        ] {
+            assert_eq!(
+                lexemes(source).unwrap(),
+                [
+                    Lexeme::Keyword(Keyword::Print),
+                    Lexeme::Symbol(Symbol::OpenParen),
+                    Lexeme::String("  ａ\nｂ".to_string()),
+                    Lexeme::Symbol(Symbol::CloseParen),
+                    Lexeme::Newline,
+                ],
+                "{}",
+                source
+            );
+        }
+    }
+}
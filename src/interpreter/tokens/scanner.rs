@@ -1,39 +1,90 @@
-use super::token::Token;
-use crate::interpreter::{error::SyntaxError, tokens::symbol::Symbol};
+use super::{
+    dictionary::Dictionary,
+    lexeme::{Lexeme, TemplatePart},
+    numeral::{
+        is_kanji_digit, is_kanji_numeral, parse_kanji_fraction, parse_kanji_integer,
+        KANJI_DECIMAL_POINT,
+    },
+    token::Token,
+};
+use crate::interpreter::{
+    error::SyntaxError,
+    normalize::{half_width, normalize},
+    span::{Location, Span},
+    tokens::symbol::Symbol,
+};
+use std::rc::Rc;
 
 pub struct Scanner {
     chars: Vec<char>,
     cursor: usize,
+    location: Location,
+    file: Option<Rc<str>>,
+    /// The dictionary keywords are read in.
+    dictionary: &'static Dictionary,
+    /// The errors found so far, each with an invalid token in its place.
+    errors: Vec<SyntaxError>,
 }
 
 impl Iterator for Scanner {
     type Item = Token;
 
+    /// A lexeme that cannot be read, such as a malformed number or an unterminated
+    /// string, is drained as a whole into an invalid token, and scanning goes on.
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(c) = self.peek_char() else {
-            return None;
-        };
+        let c = self.peek_char()?;
+        let start = self.location;
 
-        Some(match c {
-            x if x.is_symbol() => self.drain_symbol().unwrap(),
-            '"' | '”' => self.drain_string().unwrap(),
+        let token = match c {
+            '\\' | '￥' if self.ends_line(self.cursor + 1) => Ok(self.drain_continuation()),
+            x if x.is_symbol() => self.drain_symbol(),
+            '"' | '”' => self.drain_string(),
+            '『' => self.drain_raw_string(),
             '0'..='9' | '０'..='９' => self.drain_number(),
-            ' ' | '　' | '\t' => self.drain_spaces_and_tabs(),
-            '\r' | '\n' => self.drain_newline(),
+            ' ' | '　' | '\t' => Ok(self.drain_spaces_and_tabs()),
+            '\r' | '\n' => Ok(self.drain_newline()),
             '#' | '＃' | '♯' => self.drain_comment(),
-            _ => self.drain_keyword_or_identifier().unwrap(),
-        })
+            x if is_kanji_numeral(*x) => match self.drain_kanji_number() {
+                Some(token) => Ok(token),
+                None => self.drain_keyword_or_identifier(),
+            },
+            _ => self.drain_keyword_or_identifier(),
+        };
+        Some(token.unwrap_or_else(|error| {
+            self.errors.push(error);
+            self.invalid_from(start)
+        }))
     }
 }
 
 impl Scanner {
-    pub fn new(source: &str) -> Self {
+    pub fn new(source: &str, file: Option<Rc<str>>, dictionary: &'static Dictionary) -> Self {
         Self {
             chars: source.chars().collect(),
             cursor: 0,
+            location: Location::start(),
+            file,
+            dictionary,
+            errors: Vec::new(),
         }
     }
 
+    /// Returns the errors found while scanning, in the order they appear in the source.
+    pub fn into_errors(self) -> Vec<SyntaxError> {
+        self.errors
+    }
+
+    /// Returns an invalid token for the text from `start` to the current location.
+    fn invalid_from(&self, start: Location) -> Token {
+        let text = self.chars[start.char..self.cursor].iter().collect();
+        Token::invalid(text, self.span_from(start))
+    }
+
+    /// Returns the span from `start` to the current location.
+    fn span_from(&self, start: Location) -> Span {
+        Span::new(self.file.clone(), start, self.location)
+    }
+
     fn peek_char(&self) -> Option<&char> {
         self.chars.get(self.cursor)
     }
@@ -42,6 +93,7 @@ impl Scanner {
         if self.cursor < self.chars.len() {
             let c = self.chars[self.cursor];
             self.cursor += 1;
+            self.location = self.location.advance(c);
             Some(c)
         } else {
             None
@@ -49,106 +101,526 @@ impl Scanner {
     }
 
     fn drain_symbol(&mut self) -> Result<Token, SyntaxError> {
-        let position = self.cursor;
+        let start = self.location;
         let c1 = self.next_char().unwrap();
         let c2 = self.peek_char().unwrap_or(&' ');
 
-        Ok(Token::symbol(
-            match c1 {
-                '+' | '＋' => Symbol::Plus,
-                '-' | '－' | '―' | 'ー' | '‐' => Symbol::Minus,
-                '*' | '＊' | '×' => Symbol::Asterisk,
-                '/' | '／' => Symbol::Slash,
-                '=' | '＝' => match c2 {
-                    '=' | '＝' => {
-                        _ = self.next_char();
-                        Symbol::EqualEqual
-                    }
-                    _ => Symbol::Equal,
-                },
-                '!' | '！' => match c2 {
-                    '=' | '＝' => {
-                        _ = self.next_char();
-                        Symbol::BangEqual
-                    }
-                    _ => Symbol::Bang,
-                },
-                '<' | '＜' => match c2 {
-                    '=' | '＝' => {
-                        _ = self.next_char();
-                        Symbol::LessEqual
-                    }
-                    _ => Symbol::Less,
-                },
-                '>' | '＞' => match c2 {
-                    '=' | '＝' => {
-                        _ = self.next_char();
-                        Symbol::GreaterEqual
-                    }
-                    _ => Symbol::Greater,
-                },
-                '(' | '（' => Symbol::OpenParen,
-                ')' | '）' => Symbol::CloseParen,
-                '[' | '［' | '「' => Symbol::OpenBracket,
-                ']' | '］' | '」' => Symbol::CloseBracket,
-                ',' | '，' | '、' => Symbol::Comma,
-                '.' | '．' | '。' | '・' => Symbol::Bullet,
-                '~' | '～' => Symbol::Tilde,
-                _ => return Err(SyntaxError::invalid_char(c1, position)),
+        let symbol = match c1 {
+            '+' | '＋' => Symbol::Plus,
+            '-' | '－' | '―' | 'ー' | '‐' => Symbol::Minus,
+            '*' | '＊' | '×' => Symbol::Asterisk,
+            '/' | '／' => match c2 {
+                '/' | '／' => {
+                    _ = self.next_char();
+                    Symbol::SlashSlash
+                }
+                _ => Symbol::Slash,
+            },
+            '%' | '％' => Symbol::Percent,
+            '^' | '＾' => Symbol::Caret,
+            '=' | '＝' => match c2 {
+                '=' | '＝' => {
+                    _ = self.next_char();
+                    Symbol::EqualEqual
+                }
+                _ => Symbol::Equal,
+            },
+            '!' | '！' => match c2 {
+                '=' | '＝' => {
+                    _ = self.next_char();
+                    Symbol::BangEqual
+                }
+                _ => Symbol::Bang,
+            },
+            '<' | '＜' => match c2 {
+                '=' | '＝' => {
+                    _ = self.next_char();
+                    Symbol::LessEqual
+                }
+                _ => Symbol::Less,
             },
-            position,
-        ))
+            '>' | '＞' => match c2 {
+                '=' | '＝' => {
+                    _ = self.next_char();
+                    Symbol::GreaterEqual
+                }
+                _ => Symbol::Greater,
+            },
+            '(' | '（' => Symbol::OpenParen,
+            ')' | '）' => Symbol::CloseParen,
+            '[' | '［' | '「' => Symbol::OpenBracket,
+            ']' | '］' | '」' => Symbol::CloseBracket,
+            ',' | '，' | '、' => Symbol::Comma,
+            '.' | '．' | '。' | '・' => Symbol::Bullet,
+            '~' | '～' => Symbol::Tilde,
+            _ => {
+                let mut text = c1.to_string();
+                // `&&` and `||` are kept together so that one keyword can replace them.
+                if matches!(
+                    (c1, c2),
+                    ('&' | '＆', '&' | '＆') | ('|' | '｜', '|' | '｜')
+                ) {
+                    text.extend(self.next_char());
+                }
+                let span = self.span_from(start);
+                return Err(SyntaxError::invalid_char(&text, span, self.dictionary));
+            }
+        };
+        Ok(Token::symbol(symbol, self.span_from(start)))
     }
 
+    /// Drains a string literal. Expressions between `｛` and `｝` are scanned into
+    /// tokens, making the literal a template such as `”回数：｛回数｝”`.
+    ///
+    /// A literal between `”””` and `”””` is a multi-line string: when the closing
+    /// `”””` is on a line of its own, the line breaks after the opening and before
+    /// the closing delimiter are dropped, and so is its indentation on every line.
+    ///
+    /// Errors inside the literal, such as an unknown escape sequence, are reported and
+    /// the literal is drained to its end as an invalid token.
     fn drain_string(&mut self) -> Result<Token, SyntaxError> {
+        let start = self.location;
+        let reported = self.errors.len();
+        let triple = self.is_triple_quote(self.cursor);
+        let indentation = if triple {
+            for _ in 0..3 {
+                _ = self.next_char();
+            }
+            self.find_triple_quote()
+                .and_then(|end| self.indentation_before(end))
+        } else {
+            _ = self.next_char();
+            None
+        };
+        let at_end = |scanner: &Self| scanner.is_triple_quote(scanner.cursor);
+        let mut parts = Vec::new();
         let mut string = String::new();
-        _ = self.next_char();
-        let position = self.cursor;
+        if let Some(indentation) = &indentation {
+            let mut opening_line_break = String::new();
+            self.drain_line_break(indentation, at_end, &mut opening_line_break);
+        }
 
         while let Some(c) = self.peek_char() {
+            // Only a `”””` string may go on to the next line.
+            if !triple && (c == &'\r' || c == &'\n') {
+                break;
+            }
             if c == &'\\' || c == &'￥' {
-                _ = self.next_char();
-                if let Some(_) = self.peek_char() {
-                    string.push(self.next_char().unwrap());
+                match self.drain_escape() {
+                    Ok(c) => string.push(c),
+                    Err(error) => self.errors.push(error),
                 }
                 continue;
             }
-            if c == &'"' || c == &'”' {
-                _ = self.next_char();
-                break;
+            if (c == &'"' || c == &'”') && (!triple || at_end(self)) {
+                for _ in 0..if triple { 3 } else { 1 } {
+                    _ = self.next_char();
+                }
+                let span = self.span_from(start);
+                if self.errors.len() > reported {
+                    return Ok(self.invalid_from(start));
+                }
+                if parts.is_empty() {
+                    return Ok(Token::string(string, span));
+                }
+                parts.push(TemplatePart::Text(string));
+                parts.retain(|part| part != &TemplatePart::Text(String::new()));
+                return Ok(Token::template(parts, span));
+            }
+            if c == &'{' || c == &'｛' {
+                parts.push(TemplatePart::Text(std::mem::take(&mut string)));
+                match self.drain_embedded_expression() {
+                    Ok(part) => parts.push(part),
+                    Err(error) => self.errors.push(error),
+                }
+                continue;
+            }
+            if let Some(indentation) = &indentation {
+                if self.drain_line_break(indentation, at_end, &mut string) {
+                    continue;
+                }
             }
             string.push(self.next_char().unwrap());
         }
-        Ok(Token::string(string, position))
+
+        // A string on one line that already has an error, such as a `｛` left open, is
+        // not reported again for running into the end of the line.
+        if !triple && self.errors.len() > reported {
+            return Ok(self.invalid_from(start));
+        }
+        let quote = if triple { "”””" } else { "”" };
+        let span = Span::new(self.file.clone(), start, start.advance('"'));
+        Err(SyntaxError::unterminated_string(quote, span))
     }
 
-    fn drain_digits_as_string(&mut self) -> String {
-        let mut digits = String::new();
+    /// Drains a raw string literal between `『` and `』`, in which `￥` and `｛` are
+    /// written as they are. Pairs of `『` and `』` may be nested inside, and the
+    /// indentation is stripped as in a `”””` string.
+    fn drain_raw_string(&mut self) -> Result<Token, SyntaxError> {
+        let start = self.location;
+        let reported = self.errors.len();
+        _ = self.next_char();
+        let indentation = self
+            .find_raw_string_end()
+            .and_then(|end| self.indentation_before(end));
+        let at_end = |scanner: &Self| scanner.peek_char() == Some(&'』');
+        let mut string = String::new();
+        if let Some(indentation) = &indentation {
+            let mut opening_line_break = String::new();
+            self.drain_line_break(indentation, at_end, &mut opening_line_break);
+        }
+
+        let mut depth = 0;
         while let Some(c) = self.peek_char() {
             match c {
-                '0'..='9' | '０'..='９' => digits.push(self.next_char().unwrap()),
-                _ => break,
+                '』' if depth == 0 => {
+                    _ = self.next_char();
+                    if self.errors.len() > reported {
+                        return Ok(self.invalid_from(start));
+                    }
+                    return Ok(Token::string(string, self.span_from(start)));
+                }
+                '』' => depth -= 1,
+                '『' => depth += 1,
+                _ => {
+                    if let Some(indentation) = &indentation {
+                        if self.drain_line_break(indentation, at_end, &mut string) {
+                            continue;
+                        }
+                    }
+                }
             }
+            string.push(self.next_char().unwrap());
         }
-        digits
+
+        let span = Span::new(self.file.clone(), start, start.advance('『'));
+        Err(SyntaxError::unterminated_string("』", span))
+    }
+
+    fn is_triple_quote(&self, cursor: usize) -> bool {
+        self.chars
+            .get(cursor..cursor + 3)
+            .is_some_and(|chars| chars.iter().all(|c| matches!(c, '"' | '”')))
     }
 
-    fn drain_number(&mut self) -> Token {
-        let position = self.cursor;
-        let mut number = String::from(self.drain_digits_as_string().as_str());
+    /// Returns the position of the `”””` closing the string being drained.
+    fn find_triple_quote(&self) -> Option<usize> {
+        let mut cursor = self.cursor;
+        while cursor < self.chars.len() {
+            match self.chars[cursor] {
+                '\\' | '￥' => cursor += 2,
+                _ if self.is_triple_quote(cursor) => return Some(cursor),
+                _ => cursor += 1,
+            }
+        }
+        None
+    }
 
-        let Some(c) = self.peek_char() else {
-            return Token::number(number, position);
+    /// Returns the position of the `』` closing the raw string being drained.
+    fn find_raw_string_end(&self) -> Option<usize> {
+        let mut depth = 0;
+        for (cursor, c) in self.chars.iter().enumerate().skip(self.cursor) {
+            match c {
+                '『' => depth += 1,
+                '』' if depth == 0 => return Some(cursor),
+                '』' => depth -= 1,
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Returns the indentation of the closing delimiter at `end` of a multi-line
+    /// string, or `None` when the delimiter is not on a line of its own.
+    fn indentation_before(&self, end: usize) -> Option<Vec<char>> {
+        let line_start = self.chars[self.cursor..end]
+            .iter()
+            .rposition(|c| c == &'\n')?
+            + self.cursor
+            + 1;
+        let indentation = &self.chars[line_start..end];
+        indentation
+            .iter()
+            .all(|c| matches!(c, ' ' | '　' | '\t'))
+            .then(|| indentation.to_vec())
+    }
+
+    /// In a multi-line string, drains a line break and as much of `indentation` as
+    /// the next line starts with, and appends the line break to `string` unless it
+    /// is right before the closing delimiter. Returns `false` if there is no line
+    /// break here. Lines other than blank ones must start with `indentation`, and a
+    /// line that does not is reported.
+    fn drain_line_break(
+        &mut self,
+        indentation: &[char],
+        at_end: impl Fn(&Self) -> bool,
+        string: &mut String,
+    ) -> bool {
+        let line_break = match self.peek_char() {
+            Some('\r') if self.chars.get(self.cursor + 1) == Some(&'\n') => "\r\n",
+            Some('\n') => "\n",
+            _ => return false,
         };
-        if c == &'.' || c == &'．' {
-            number.push(self.next_char().unwrap());
-            number.push_str(self.drain_digits_as_string().as_str());
+        for _ in line_break.chars() {
+            _ = self.next_char();
+        }
+        let start = self.location;
+        for c in indentation {
+            if self.peek_char() != Some(c) {
+                if !matches!(self.peek_char(), Some('\r' | '\n') | None) {
+                    let span = self.span_from(start);
+                    self.errors.push(SyntaxError::shallow_string_line(span));
+                }
+                break;
+            }
+            _ = self.next_char();
+        }
+        if !at_end(self) {
+            string.push_str(line_break);
+        }
+        true
+    }
+
+    /// Drains an escape sequence such as `￥ｎ` or `\u{3042}` and returns the character
+    /// it stands for. Letters may be written in full-width, and `￥￥`, `￥”` and
+    /// `￥｛` keep the character as it is written.
+    fn drain_escape(&mut self) -> Result<char, SyntaxError> {
+        let start = self.location;
+        let start_cursor = self.cursor;
+        let backslash = self.next_char().unwrap();
+        if matches!(self.peek_char(), Some('\r' | '\n') | None) {
+            let span = self.span_from(start);
+            return Err(SyntaxError::invalid_escape(&backslash.to_string(), span));
+        }
+
+        let escaped = match self.next_char().unwrap() {
+            c @ ('\\' | '￥' | '"' | '”' | '{' | '｛' | '}' | '｝') => Some(c),
+            c => match half_width(c) {
+                'n' => Some('\n'),
+                't' => Some('\t'),
+                'r' => Some('\r'),
+                'u' => self.drain_unicode_escape(),
+                _ => None,
+            },
+        };
+        escaped.ok_or_else(|| {
+            let sequence = self.chars[start_cursor..self.cursor]
+                .iter()
+                .collect::<String>();
+            SyntaxError::invalid_escape(&sequence, self.span_from(start))
+        })
+    }
+
+    /// Drains the `{XXXX}` part of `\u{XXXX}`, one to six hexadecimal digits.
+    fn drain_unicode_escape(&mut self) -> Option<char> {
+        if self.peek_half_width(0) != Some('{') {
+            return None;
+        }
+        _ = self.next_char();
+
+        let mut digits = String::new();
+        while let Some(c) = self.peek_half_width(0).filter(char::is_ascii_hexdigit) {
+            _ = self.next_char();
+            digits.push(c);
+        }
+        if self.peek_half_width(0) != Some('}') || digits.len() > 6 {
+            return None;
         }
-        Token::number(number, position)
+        _ = self.next_char();
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+    }
+
+    /// Drains an expression embedded in a string literal, from `｛` to `｝`.
+    fn drain_embedded_expression(&mut self) -> Result<TemplatePart, SyntaxError> {
+        let start = self.location;
+        _ = self.next_char();
+
+        let mut tokens = Vec::new();
+        loop {
+            match self.peek_char() {
+                Some('}' | '｝') => {
+                    _ = self.next_char();
+                    let span = self.span_from(start);
+                    if tokens.is_empty() {
+                        let detail = "「｛」と「｝」のあいだに式がありません";
+                        return Err(SyntaxError::malformed_interpolation(detail, span));
+                    }
+                    return Ok(TemplatePart::Expression(tokens, span));
+                }
+                Some('\r' | '\n') | None => {
+                    let detail = "「｛」が同じ行の「｝」で閉じられていません";
+                    let span = self.span_from(start);
+                    return Err(SyntaxError::malformed_interpolation(detail, span));
+                }
+                _ => (),
+            }
+            let token = self.next().unwrap();
+            if !matches!(token.lexeme, Lexeme::Spaces(_) | Lexeme::Comment) {
+                tokens.push(token);
+            }
+        }
+    }
+
+    /// Drains a number literal: `１２３`, `３．１４`, `6.02e23`, `１＿０００`, `0xFF`,
+    /// `0b1010` or `0o17`, in half-width or full-width. The lexeme is normalized into
+    /// half-width decimal text that Rust can parse, such as `255` for `0xFF`.
+    fn drain_number(&mut self) -> Result<Token, SyntaxError> {
+        let start = self.location;
+        let start_cursor = self.cursor;
+
+        let prefix = self
+            .peek_half_width(1)
+            .filter(|_| self.peek_half_width(0) == Some('0'));
+        let radix = match prefix {
+            Some('x' | 'X') => 16,
+            Some('o' | 'O') => 8,
+            Some('b' | 'B') => 2,
+            _ => 10,
+        };
+        let number = if radix == 10 {
+            self.drain_decimal()
+        } else {
+            _ = self.next_char();
+            _ = self.next_char();
+            self.drain_digits(radix).and_then(|digits| {
+                u64::from_str_radix(&digits, radix)
+                    .map(|number| number.to_string())
+                    .map_err(|_| "大きすぎて表せない数です")
+            })
+        };
+
+        // A number must not run into letters or digits it cannot contain, as in `0b102`.
+        let detail = match self.peek_half_width(0) {
+            Some(c) if c.is_ascii_digit() => Err("この進数では使えない数字があります"),
+            Some(c) if c.is_ascii_alphanumeric() || c == '_' => {
+                Err("数字のあとに続けて英字は書けません")
+            }
+            _ => Ok(()),
+        };
+        match number.and_then(|number| detail.map(|_| number)) {
+            Ok(number) => Ok(Token::number(number, self.span_from(start))),
+            Err(detail) => {
+                while self
+                    .peek_half_width(0)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+                {
+                    _ = self.next_char();
+                }
+                let literal = self.chars[start_cursor..self.cursor]
+                    .iter()
+                    .collect::<String>();
+                Err(SyntaxError::malformed_number(
+                    &literal,
+                    detail,
+                    self.span_from(start),
+                ))
+            }
+        }
+    }
+
+    fn drain_decimal(&mut self) -> Result<String, &'static str> {
+        let mut number = self.drain_digits(10)?;
+
+        if self.peek_half_width(0) == Some('.') {
+            _ = self.next_char();
+            number.push('.');
+            if self.peek_half_width(0).is_some_and(|c| c.is_ascii_digit()) {
+                number.push_str(&self.drain_digits(10)?);
+            }
+        }
+
+        if let Some('e' | 'E') = self.peek_half_width(0) {
+            _ = self.next_char();
+            number.push('e');
+            match self.peek_char() {
+                Some('+' | '＋') => _ = self.next_char(),
+                Some('-' | '－' | '―' | 'ー' | '‐') => {
+                    _ = self.next_char();
+                    number.push('-');
+                }
+                _ => (),
+            }
+            let exponent = self
+                .drain_digits(10)
+                .map_err(|_| "指数の数字がありません")?;
+            number.push_str(&exponent);
+        }
+        Ok(number)
+    }
+
+    /// Drains digits of `radix`, which may be separated by `_` or `＿` as in `１＿０００`.
+    fn drain_digits(&mut self, radix: u32) -> Result<String, &'static str> {
+        const SEPARATOR: &str = "区切りの「_」は数字と数字のあいだにだけ書けます";
+        let mut digits = String::new();
+        let mut separated = false;
+
+        while let Some(c) = self.peek_half_width(0) {
+            if c == '_' {
+                if digits.is_empty() || separated {
+                    return Err(SEPARATOR);
+                }
+                separated = true;
+            } else if c.is_digit(radix) {
+                digits.push(c);
+                separated = false;
+            } else {
+                break;
+            }
+            _ = self.next_char();
+        }
+
+        if separated {
+            Err(SEPARATOR)
+        } else if digits.is_empty() {
+            Err("数字がありません")
+        } else {
+            Ok(digits)
+        }
+    }
+
+    /// Returns the character `offset` characters ahead, converted into half-width.
+    fn peek_half_width(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.cursor + offset).map(|c| half_width(*c))
+    }
+
+    /// Drains a kanji numeral such as `百二十三` or `三・一四`.
+    ///
+    /// A run of kanji numeral characters is a number only when it is well-formed and
+    /// is not followed by other characters of an identifier, so `十分` and `一番` stay
+    /// identifiers. Returns `None` without draining anything when it is not a number.
+    fn drain_kanji_number(&mut self) -> Option<Token> {
+        let rest = &self.chars[self.cursor..];
+        let mut len = rest.iter().take_while(|c| is_kanji_numeral(**c)).count();
+        let mut number = parse_kanji_integer(&rest[..len])?;
+
+        if rest.get(len) == Some(&KANJI_DECIMAL_POINT) {
+            let fraction = &rest[len + 1..];
+            let fraction_len = fraction.iter().take_while(|c| is_kanji_digit(**c)).count();
+            if fraction_len > 0 {
+                number.push('.');
+                number.push_str(&parse_kanji_fraction(&fraction[..fraction_len])?);
+                len += 1 + fraction_len;
+            }
+        }
+        if rest
+            .get(len)
+            .is_some_and(|c| !c.is_symbol() && !c.is_special())
+        {
+            return None;
+        }
+
+        let start = self.location;
+        for _ in 0..len {
+            _ = self.next_char();
+        }
+        Some(Token::number(number, self.span_from(start)))
     }
 
     fn drain_spaces_and_tabs(&mut self) -> Token {
-        let position = self.cursor;
+        let start = self.location;
         let mut spaces = String::new();
 
         while let Some(c) = self.peek_char() {
@@ -159,16 +631,51 @@ impl Scanner {
                 _ => break,
             }
         }
-        Token::spaces(spaces.len(), position)
+        Token::spaces(spaces, self.span_from(start))
+    }
+
+    /// Returns `true` if only spaces follow `cursor` up to the end of the line.
+    fn ends_line(&self, cursor: usize) -> bool {
+        let mut rest = self.chars[cursor..].iter();
+        let next = rest.find(|c| !matches!(c, ' ' | '　' | '\t'));
+        matches!(next, Some('\r' | '\n') | None)
+    }
+
+    /// Drains `￥` or `\\` at the end of a line, which continues the line.
+    fn drain_continuation(&mut self) -> Token {
+        let start = self.location;
+        _ = self.next_char();
+        Token::continuation(self.span_from(start))
     }
 
     fn drain_newline(&mut self) -> Token {
-        self.cursor += 1;
-        Token::newline()
+        let start = self.location;
+        _ = self.next_char();
+        Token::newline(self.span_from(start))
     }
 
-    fn drain_comment(&mut self) -> Token {
-        let position = self.cursor;
+    /// Drains a comment: `＃` to the end of the line, a documentation comment `＃＃`
+    /// to the end of the line, or a block comment from `＃｛` to `｝＃`.
+    fn drain_comment(&mut self) -> Result<Token, SyntaxError> {
+        let start = self.location;
+        _ = self.next_char();
+
+        match self.peek_char() {
+            Some('{' | '｛') => return self.drain_block_comment(start),
+            Some('#' | '＃' | '♯') if !self.is_comment_mark(self.cursor + 1) => {
+                _ = self.next_char();
+                if matches!(self.peek_char(), Some(' ' | '　')) {
+                    _ = self.next_char();
+                }
+                let mut doc = String::new();
+                while let Some(c) = self.peek_char().filter(|c| !matches!(c, '\r' | '\n')) {
+                    doc.push(*c);
+                    _ = self.next_char();
+                }
+                return Ok(Token::doc_comment(doc, self.span_from(start)));
+            }
+            _ => (),
+        }
 
         while let Some(c) = self.peek_char() {
             match c {
@@ -176,17 +683,53 @@ impl Scanner {
                 _ => _ = self.next_char().unwrap(),
             }
         }
-        Token::comment(position)
+        Ok(Token::comment(self.span_from(start)))
+    }
+
+    /// Drains a block comment after its opening `＃`. Block comments may be nested.
+    fn drain_block_comment(&mut self, start: Location) -> Result<Token, SyntaxError> {
+        _ = self.next_char();
+        let mut depth = 0;
+        while let Some(c) = self.next_char() {
+            match c {
+                '#' | '＃' | '♯' if matches!(self.peek_char(), Some('{' | '｛')) => {
+                    _ = self.next_char();
+                    depth += 1;
+                }
+                '}' | '｝' if self.is_comment_mark(self.cursor) => {
+                    _ = self.next_char();
+                    if depth == 0 {
+                        return Ok(Token::comment(self.span_from(start)));
+                    }
+                    depth -= 1;
+                }
+                _ => (),
+            }
+        }
+
+        let opening = Span::new(self.file.clone(), start, start.advance('#').advance('{'));
+        Err(SyntaxError::unterminated_comment(opening))
+    }
+
+    fn is_comment_mark(&self, cursor: usize) -> bool {
+        matches!(self.chars.get(cursor), Some('#' | '＃' | '♯'))
     }
 
     fn drain_keyword_or_identifier(&mut self) -> Result<Token, SyntaxError> {
         if let Some(c) = self.peek_char() {
             if c.is_special() {
-                return Err(SyntaxError::invalid_char(*c, self.cursor));
+                let start = self.location;
+                let c = *c;
+                _ = self.next_char();
+                return Err(SyntaxError::invalid_char(
+                    &c.to_string(),
+                    self.span_from(start),
+                    self.dictionary,
+                ));
             }
         }
 
-        let position = self.cursor;
+        let start = self.location;
         let mut identifier = String::new();
 
         while let Some(c) = self.peek_char() {
@@ -195,11 +738,13 @@ impl Scanner {
                 _ => identifier.push(self.next_char().unwrap()),
             }
         }
-        Ok(if let Ok(keyword) = identifier.parse() {
-            Token::keyword(keyword, position)
-        } else {
-            Token::identifier(identifier, position)
-        })
+        Ok(
+            if let Some(keyword) = self.dictionary.keyword(&normalize(&identifier)) {
+                Token::keyword(keyword, self.span_from(start))
+            } else {
+                Token::identifier(identifier, self.span_from(start))
+            },
+        )
     }
 }
 
@@ -209,15 +754,72 @@ trait IsSymbol {
 
 impl IsSymbol for char {
     fn is_symbol(&self) -> bool {
-        match self {
-            '+' | '＋' | '-' | '－' | '―' | 'ー' | '‐' | '*' | '＊' | '×' | '/' | '／' | '='
-            | '＝' | '!' | '！' | '<' | '＜' | '>' | '＞' | '&' | '＆' | '|' | '｜' | '('
-            | '（' | ')' | '）' | '[' | '［' | ']' | '］' | '「' | '」' | '\\' | '￥' | '~'
-            | '～' | ':' | '：' | ';' | '；' | ',' | '，' | '、' | '@' | '＠' | '$' | '＄'
-            | '%' | '％' | '^' | '＾' | '.' | '。' | '・' | '\'' | '’' | '{' | '｛' | '}'
-            | '｝' | '`' | '‘' => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            '+' | '＋'
+                | '-'
+                | '－'
+                | '―'
+                | 'ー'
+                | '‐'
+                | '*'
+                | '＊'
+                | '×'
+                | '/'
+                | '／'
+                | '='
+                | '＝'
+                | '!'
+                | '！'
+                | '<'
+                | '＜'
+                | '>'
+                | '＞'
+                | '&'
+                | '＆'
+                | '|'
+                | '｜'
+                | '('
+                | '（'
+                | ')'
+                | '）'
+                | '['
+                | '［'
+                | ']'
+                | '］'
+                | '「'
+                | '」'
+                | '\\'
+                | '￥'
+                | '~'
+                | '～'
+                | ':'
+                | '：'
+                | ';'
+                | '；'
+                | ','
+                | '，'
+                | '、'
+                | '@'
+                | '＠'
+                | '$'
+                | '＄'
+                | '%'
+                | '％'
+                | '^'
+                | '＾'
+                | '.'
+                | '。'
+                | '・'
+                | '\''
+                | '’'
+                | '{'
+                | '｛'
+                | '}'
+                | '｝'
+                | '`'
+                | '‘'
+        )
     }
 }
 
@@ -227,9 +829,311 @@ trait IsSpecial {
 
 impl IsSpecial for char {
     fn is_special(&self) -> bool {
-        match self {
-            ' ' | '　' | '\t' | '\r' | '\n' | '"' | '”' | '#' | '＃' | '♯' => true,
-            _ => false,
+        matches!(
+            self,
+            ' ' | '　' | '\t' | '\r' | '\n' | '"' | '”' | '#' | '＃' | '♯'
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::tokens::dictionary::STANDARD;
+
+    fn scan(source: &str) -> (Vec<Lexeme>, Vec<&'static str>) {
+        let mut scanner = Scanner::new(source, None, &STANDARD);
+        let lexemes = scanner
+            .by_ref()
+            .map(|token| token.lexeme)
+            .filter(|lexeme| !matches!(lexeme, Lexeme::Spaces(_)))
+            .collect();
+        let codes = scanner
+            .into_errors()
+            .iter()
+            .map(|error| error.code)
+            .collect();
+        (lexemes, codes)
+    }
+
+    #[test]
+    fn keeps_scanning_after_lexical_errors() {
+        let (lexemes, codes) = scan("0b102 ＠ ”a￥qb” ｘ");
+        assert_eq!(codes, ["S0007", "S0001", "S0009"]);
+        assert_eq!(
+            lexemes,
+            [
+                Lexeme::Invalid("0b102".to_string()),
+                Lexeme::Invalid("＠".to_string()),
+                Lexeme::Invalid("”a￥qb”".to_string()),
+                Lexeme::Identifier("ｘ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_an_unterminated_string_once() {
+        let (lexemes, codes) = scan("”ａ｛ｂ\nｃ");
+        assert_eq!(codes, ["S0008"]);
+        assert_eq!(lexemes[0], Lexeme::Invalid("”ａ｛ｂ".to_string()));
+    }
+
+    /// Returns the only error found in `source`.
+    fn error(source: &str) -> SyntaxError {
+        let mut scanner = Scanner::new(source, None, &STANDARD);
+        scanner.by_ref().for_each(drop);
+        let mut errors = scanner.into_errors();
+        assert_eq!(errors.len(), 1, "{}", source);
+        errors.remove(0)
+    }
+
+    #[test]
+    fn suggests_keywords_for_operators_of_other_languages() {
+        for (source, text, replacement) in [
+            ("ａ && ｂ", "&&", "かつ"),
+            ("ａ ＆＆ ｂ", "＆＆", "かつ"),
+            ("ａ & ｂ", "&", "かつ"),
+            ("ａ || ｂ", "||", "または"),
+            ("ａ ｜ ｂ", "｜", "または"),
+        ] {
+            let error = error(source);
+            assert_eq!(error.code, "S0001");
+            assert_eq!(error.message, format!("使えない文字「{}」があります", text));
+            let suggestion = &error.suggestions[0];
+            assert_eq!(suggestion.replacement, replacement);
+            let span = &suggestion.span;
+            assert_eq!(&source[span.start.byte..span.end.byte], text);
+        }
+    }
+
+    #[test]
+    fn hints_at_what_other_symbols_are_written_as() {
+        for (source, hint) in [
+            ("’ａ", "「”」"),
+            ("ｘ ＝ １；", "改行"),
+            ("もし 真：", "「なら」"),
+            ("｛ｘ", "文字列の中で"),
+        ] {
+            let error = error(source);
+            assert_eq!(error.code, "S0001", "{}", source);
+            assert!(error.hints[0].contains(hint), "{}", source);
+        }
+    }
+
+    fn number(source: &str) -> Lexeme {
+        let (lexemes, codes) = scan(source);
+        assert!(codes.is_empty(), "{} scans without errors", source);
+        assert_eq!(lexemes.len(), 1, "{} is one token", source);
+        lexemes[0].clone()
+    }
+
+    fn malformed(source: &str) -> Option<String> {
+        let mut scanner = Scanner::new(source, None, &STANDARD);
+        let tokens = scanner.by_ref().collect::<Vec<_>>();
+        assert_eq!(
+            tokens.first().map(|token| &token.lexeme),
+            Some(&Lexeme::Invalid(source.to_string()))
+        );
+        let error = scanner.into_errors().into_iter().next()?;
+        assert_eq!(error.code, "S0007");
+        error.notes.into_iter().next()
+    }
+
+    #[test]
+    fn reads_radix_literals() {
+        assert_eq!(number("0xFF"), Lexeme::Number("255".to_string()));
+        assert_eq!(number("０ｘｆｆ"), Lexeme::Number("255".to_string()));
+        assert_eq!(number("0o17"), Lexeme::Number("15".to_string()));
+        assert_eq!(number("0b1010"), Lexeme::Number("10".to_string()));
+        assert_eq!(number("0b1111_0000"), Lexeme::Number("240".to_string()));
+    }
+
+    #[test]
+    fn reads_exponents() {
+        assert_eq!(number("6.02e23"), Lexeme::Number("6.02e23".to_string()));
+        assert_eq!(number("１Ｅ－３"), Lexeme::Number("1e-3".to_string()));
+        assert_eq!(number("2e+5"), Lexeme::Number("2e5".to_string()));
+    }
+
+    #[test]
+    fn reads_separators() {
+        assert_eq!(number("１＿０００"), Lexeme::Number("1000".to_string()));
+        assert_eq!(
+            number("1_000.000_1"),
+            Lexeme::Number("1000.0001".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_numbers() {
+        let separator = "区切りの「_」は数字と数字のあいだにだけ書けます";
+        assert_eq!(malformed("1__000").as_deref(), Some(separator));
+        assert_eq!(malformed("1000_").as_deref(), Some(separator));
+        assert_eq!(
+            malformed("0b102").as_deref(),
+            Some("この進数では使えない数字があります")
+        );
+        assert_eq!(malformed("0x").as_deref(), Some("数字がありません"));
+        assert_eq!(malformed("1e").as_deref(), Some("指数の数字がありません"));
+        assert_eq!(
+            malformed("12ab").as_deref(),
+            Some("数字のあとに続けて英字は書けません")
+        );
+        assert_eq!(
+            malformed("0xFFFFFFFFFFFFFFFFF").as_deref(),
+            Some("大きすぎて表せない数です")
+        );
+    }
+
+    #[test]
+    fn nests_block_comments() {
+        let (lexemes, codes) = scan("＃｛ ａ ＃｛ ｂ ｝＃ ｃ ｝＃ ｘ");
+        assert!(codes.is_empty());
+        assert_eq!(
+            lexemes,
+            [Lexeme::Comment, Lexeme::Identifier("ｘ".to_string())]
+        );
+    }
+
+    #[test]
+    fn reports_an_unterminated_block_comment() {
+        let (lexemes, codes) = scan("＃｛ ａ ＃｛ ｂ ｝＃\nｘ");
+        assert_eq!(codes, ["S0012"]);
+        assert_eq!(lexemes.len(), 1);
+    }
+
+    #[test]
+    fn reads_doc_comments() {
+        let (lexemes, _) = scan("＃＃ 二倍にする\n＃＃\n＃ ふつう");
+        assert_eq!(
+            lexemes,
+            [
+                Lexeme::DocComment("二倍にする".to_string()),
+                Lexeme::Newline,
+                Lexeme::DocComment(String::new()),
+                Lexeme::Newline,
+                Lexeme::Comment,
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_escapes() {
+        for (source, string) in [
+            (
+                r#""a\nb\tc\rd\\e\"f\{g\}h\u{3042}""#,
+                "a\nb\tc\rd\\e\"f{g}hあ",
+            ),
+            (
+                "”ａ￥ｎｂ￥ｔｃ￥ｒｄ￥￥ｅ￥”ｆ￥｛ｇ￥｝ｈ￥ｕ｛３０４２｝”",
+                "ａ\nｂ\tｃ\rｄ￥ｅ”ｆ｛ｇ｝ｈあ",
+            ),
+        ] {
+            let (lexemes, codes) = scan(source);
+            assert!(codes.is_empty(), "{}", source);
+            assert_eq!(lexemes, [Lexeme::String(string.to_string())]);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_escapes() {
+        for (source, sequence) in [
+            ("”ａ￥ｑ”", "「￥ｑ」"),
+            (r#""\u{}""#, r"「\u{}」"),
+            (r#""\u{110000}""#, r"「\u{110000}」"),
+            (r#""\u{1234567}""#, r"「\u{1234567"),
+        ] {
+            let mut scanner = Scanner::new(source, None, &STANDARD);
+            let lexemes = scanner
+                .by_ref()
+                .map(|token| token.lexeme)
+                .collect::<Vec<_>>();
+            let errors = scanner.into_errors();
+            assert_eq!(lexemes, [Lexeme::Invalid(source.to_string())], "{}", source);
+            assert_eq!(errors.len(), 1, "{}", source);
+            assert_eq!(errors[0].code, "S0009", "{}", source);
+            assert!(
+                errors[0].message.contains(sequence),
+                "{}",
+                errors[0].message
+            );
+        }
+    }
+
+    #[test]
+    fn reports_unterminated_strings() {
+        for (source, column) in [
+            ("表示（”ａｂｃ", 4),
+            ("表示（”ａｂｃ\n表示（１）\n", 4),
+            ("ｘ ＝ ”””\n  ａ\n", 5),
+        ] {
+            let mut scanner = Scanner::new(source, None, &STANDARD);
+            scanner.by_ref().for_each(drop);
+            let errors = scanner.into_errors();
+            assert_eq!(
+                errors.iter().map(|error| error.code).collect::<Vec<_>>(),
+                ["S0010"],
+                "{:?}",
+                source
+            );
+            let span = errors[0].span.as_deref().unwrap();
+            assert_eq!(
+                (span.start.line, span.start.column),
+                (1, column),
+                "{:?}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn strips_the_indentation_of_the_closing_delimiter() {
+        let source = "ｘ ＝ ”””\n      一行目\n        二行目\n\n      三行目\n      ”””\n";
+        let (lexemes, codes) = scan(source);
+        assert!(codes.is_empty());
+        assert_eq!(
+            lexemes[2],
+            Lexeme::String("一行目\n  二行目\n\n三行目".to_string())
+        );
+        assert_eq!(lexemes[3..], [Lexeme::Newline]);
+    }
+
+    #[test]
+    fn keeps_a_multiline_string_as_written_without_a_closing_line() {
+        let (lexemes, codes) = scan("”””一行目\n  二行目”””");
+        assert!(codes.is_empty());
+        assert_eq!(lexemes, [Lexeme::String("一行目\n  二行目".to_string())]);
+    }
+
+    #[test]
+    fn reads_raw_strings_as_written() {
+        let (lexemes, codes) = scan(r"『ａ￥ｎ\t｛ｂ｝『ｃ』』");
+        assert!(codes.is_empty());
+        assert_eq!(
+            lexemes,
+            [Lexeme::String(r"ａ￥ｎ\t｛ｂ｝『ｃ』".to_string())]
+        );
+
+        let (lexemes, codes) = scan("『\n    ＼｛\n      ｝\n    』");
+        assert!(codes.is_empty());
+        assert_eq!(lexemes, [Lexeme::String("＼｛\n  ｝".to_string())]);
+
+        let (_, codes) = scan("ｘ ＝ 『ａ『ｂ』\n");
+        assert_eq!(codes, ["S0010"]);
+    }
+
+    #[test]
+    fn reports_lines_shallower_than_the_closing_delimiter() {
+        for source in [
+            "”””\n    一行目\n  二行目\n    ”””",
+            "『\n    一行目\n  二行目\n    』",
+        ] {
+            let mut scanner = Scanner::new(source, None, &STANDARD);
+            scanner.by_ref().for_each(drop);
+            let errors = scanner.into_errors();
+            assert_eq!(errors.len(), 1, "{}", source);
+            assert_eq!(errors[0].code, "S0011", "{}", source);
+            assert_eq!(errors[0].span.as_deref().unwrap().start.line, 3);
         }
     }
 }
@@ -1,9 +1,15 @@
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Symbol {
     Plus,
     Minus,
     Asterisk,
     Slash,
+    /// `／／`, division rounded down
+    SlashSlash,
+    Percent,
+    Caret,
     Equal,
     EqualEqual,
     Bang,
@@ -21,29 +27,32 @@ pub enum Symbol {
     Tilde,
 }
 
-impl ToString for Symbol {
-    fn to_string(&self) -> String {
-        match self {
-            Symbol::Plus => "+",
-            Symbol::Minus => "-",
-            Symbol::Asterisk => "*",
-            Symbol::Slash => "/",
-            Symbol::Equal => "=",
-            Symbol::EqualEqual => "==",
-            Symbol::Bang => "!",
-            Symbol::BangEqual => "!=",
-            Symbol::Less => "<",
-            Symbol::LessEqual => "<=",
-            Symbol::Greater => ">",
-            Symbol::GreaterEqual => ">=",
-            Symbol::OpenParen => "(",
-            Symbol::CloseParen => ")",
-            Symbol::OpenBracket => "[",
-            Symbol::CloseBracket => "]",
-            Symbol::Comma => ",",
-            Symbol::Bullet => ".",
-            Symbol::Tilde => "~",
-        }
-        .to_string()
+/// Symbols are spelled in full-width, as students write them.
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Symbol::Plus => "＋",
+            Symbol::Minus => "ー",
+            Symbol::Asterisk => "＊",
+            Symbol::Slash => "／",
+            Symbol::SlashSlash => "／／",
+            Symbol::Percent => "％",
+            Symbol::Caret => "＾",
+            Symbol::Equal => "＝",
+            Symbol::EqualEqual => "＝＝",
+            Symbol::Bang => "！",
+            Symbol::BangEqual => "！＝",
+            Symbol::Less => "＜",
+            Symbol::LessEqual => "＜＝",
+            Symbol::Greater => "＞",
+            Symbol::GreaterEqual => "＞＝",
+            Symbol::OpenParen => "（",
+            Symbol::CloseParen => "）",
+            Symbol::OpenBracket => "「",
+            Symbol::CloseBracket => "」",
+            Symbol::Comma => "、",
+            Symbol::Bullet => "・",
+            Symbol::Tilde => "～",
+        })
     }
 }
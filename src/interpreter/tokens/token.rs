@@ -1,87 +1,112 @@
-use super::{keyword::Keyword, lexeme::Lexeme, symbol::Symbol};
-use std::cmp::min;
+use super::{
+    keyword::Keyword,
+    lexeme::{Lexeme, TemplatePart},
+    symbol::Symbol,
+};
+use crate::interpreter::span::Span;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub lexeme: Lexeme,
-    pub position: i32,
+    pub span: Span,
 }
 
 impl Token {
-    pub fn symbol(value: Symbol, position: usize) -> Token {
+    pub fn symbol(value: Symbol, span: Span) -> Token {
         Token {
             lexeme: Lexeme::Symbol(value),
-            position: min(position, i32::MAX as usize) as i32,
+            span,
         }
     }
 
-    pub fn keyword(value: Keyword, position: usize) -> Token {
+    pub fn keyword(value: Keyword, span: Span) -> Token {
         Token {
             lexeme: Lexeme::Keyword(value),
-            position: min(position, i32::MAX as usize) as i32,
+            span,
         }
     }
 
-    pub fn identifier(value: String, position: usize) -> Token {
+    pub fn identifier(value: String, span: Span) -> Token {
         Token {
             lexeme: Lexeme::Identifier(value),
-            position: min(position, i32::MAX as usize) as i32,
+            span,
         }
     }
 
-    pub fn string(value: String, position: usize) -> Token {
+    pub fn string(value: String, span: Span) -> Token {
         Token {
             lexeme: Lexeme::String(value),
-            position: min(position, i32::MAX as usize) as i32,
+            span,
         }
     }
 
-    pub fn number(value: String, position: usize) -> Token {
+    pub fn template(parts: Vec<TemplatePart>, span: Span) -> Token {
+        Token {
+            lexeme: Lexeme::Template(parts),
+            span,
+        }
+    }
+
+    pub fn number(value: String, span: Span) -> Token {
         Token {
             lexeme: Lexeme::Number(value),
-            position: min(position, i32::MAX as usize) as i32,
+            span,
         }
     }
 
-    pub fn spaces(len: usize, position: usize) -> Token {
+    pub fn spaces(spaces: String, span: Span) -> Token {
         Token {
-            lexeme: Lexeme::Spaces(len),
-            position: min(position, i32::MAX as usize) as i32,
+            lexeme: Lexeme::Spaces(spaces),
+            span,
         }
     }
 
-    pub fn newline() -> Token {
+    pub fn newline(span: Span) -> Token {
         Token {
             lexeme: Lexeme::Newline,
-            position: -1,
+            span,
+        }
+    }
+
+    pub fn continuation(span: Span) -> Token {
+        Token {
+            lexeme: Lexeme::Continuation,
+            span,
         }
     }
 
-    pub fn comment(position: usize) -> Token {
+    pub fn comment(span: Span) -> Token {
         Token {
             lexeme: Lexeme::Comment,
-            position: min(position, i32::MAX as usize) as i32,
+            span,
+        }
+    }
+
+    pub fn doc_comment(doc: String, span: Span) -> Token {
+        Token {
+            lexeme: Lexeme::DocComment(doc),
+            span,
         }
     }
 
-    pub fn indent() -> Token {
+    pub fn indent(span: Span) -> Token {
         Token {
             lexeme: Lexeme::Indent,
-            position: -1,
+            span,
         }
     }
 
-    pub fn dedent() -> Token {
+    pub fn dedent(span: Span) -> Token {
         Token {
             lexeme: Lexeme::Dedent,
-            position: -1,
+            span,
         }
     }
 
-    pub fn invalid(c: char, position: usize) -> Token {
+    pub fn invalid(text: String, span: Span) -> Token {
         Token {
-            lexeme: Lexeme::Invalid(c),
-            position: min(position, i32::MAX as usize) as i32,
+            lexeme: Lexeme::Invalid(text),
+            span,
         }
     }
 }
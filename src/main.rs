@@ -1,14 +1,260 @@
-use crate::interpreter::ast::Ast;
-use crate::interpreter::tokens::Tokens;
+use crate::cli::{Command, Input, MessageFormat, Options};
+use crate::interpreter::ast::{node::Node, Ast};
+use crate::interpreter::checker;
+use crate::interpreter::cst::Cst;
+use crate::interpreter::diagnostic::{Diagnostic, Renderer};
+use crate::interpreter::error::catalog;
+use crate::interpreter::json::Json;
+use crate::interpreter::tokens::{
+    dictionary::Dictionary, indentation::Indentation, keyword::Keyword,
+};
 use crate::interpreter::Interpreter;
+use std::env;
 use std::fs::read_to_string;
-use std::str::FromStr;
+use std::io::{self, Read};
+use std::process::ExitCode;
+use std::thread;
 
+mod cli;
 mod interpreter;
+mod repl;
 
-fn main() {
-    let src = read_to_string("./Shikibu/main.skb").unwrap();
-    let tokens = Tokens::from_str(src.as_str()).unwrap();
-    let ast = Ast::try_from(tokens).unwrap();
-    Interpreter::run(ast).unwrap();
+fn main() -> ExitCode {
+    thread::Builder::new()
+        .stack_size(interpreter::STACK_SIZE)
+        .spawn(start)
+        .map(|handle| handle.join().unwrap_or(ExitCode::FAILURE))
+        .unwrap_or_else(|error| {
+            eprintln!("shikibu: 実行を始められませんでした: {}", error);
+            ExitCode::FAILURE
+        })
+}
+
+fn start() -> ExitCode {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("shikibu: {}", message);
+            eprintln!();
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Command::Version => {
+            println!("shikibu {}", env!("CARGO_PKG_VERSION"));
+            ExitCode::SUCCESS
+        }
+        Command::Explain(code) => match catalog::explain(&code) {
+            Some(explanation) => {
+                print!("{}", explanation);
+                ExitCode::SUCCESS
+            }
+            None => {
+                eprintln!("shikibu: エラー番号 {} は見つかりませんでした", code);
+                ExitCode::FAILURE
+            }
+        },
+        Command::Run(options) => run(options),
+        Command::Check(options) => check(options),
+        Command::Doc(options) => doc(options),
+    }
+}
+
+fn run(options: Options) -> ExitCode {
+    let color = options.color.enabled();
+    let format = options.message_format;
+    let Some(input) = options.input else {
+        return match repl::start(color, &options.indentation, options.keywords) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("shikibu: {}", error);
+                ExitCode::FAILURE
+            }
+        };
+    };
+
+    let Some((src, ast, dictionary)) = load(
+        &input,
+        color,
+        format,
+        &options.indentation,
+        options.keywords,
+    ) else {
+        return ExitCode::FAILURE;
+    };
+    if let Err(error) = Interpreter::run(ast, options.args, dictionary) {
+        report(
+            &src,
+            Diagnostic::from(&error),
+            color,
+            format,
+            &options.indentation,
+        );
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn check(options: Options) -> ExitCode {
+    let color = options.color.enabled();
+    let format = options.message_format;
+    let Some(input) = options.input else {
+        return ExitCode::FAILURE;
+    };
+    let Some((src, ast, dictionary)) = load(
+        &input,
+        color,
+        format,
+        &options.indentation,
+        options.keywords,
+    ) else {
+        return ExitCode::FAILURE;
+    };
+
+    let errors = checker::check(&ast, dictionary);
+    for error in &errors {
+        report(
+            &src,
+            Diagnostic::from(error),
+            color,
+            format,
+            &options.indentation,
+        );
+    }
+    if !errors.is_empty() {
+        return ExitCode::FAILURE;
+    }
+    if format == MessageFormat::Human {
+        println!("{}: まちがいは見つかりませんでした", input.name());
+    }
+    ExitCode::SUCCESS
+}
+
+/// Prints each function with its parameters and documentation comment.
+fn doc(options: Options) -> ExitCode {
+    let color = options.color.enabled();
+    let Some(input) = options.input else {
+        return ExitCode::FAILURE;
+    };
+    let Some((_, ast, dictionary)) = load(
+        &input,
+        color,
+        options.message_format,
+        &options.indentation,
+        options.keywords,
+    ) else {
+        return ExitCode::FAILURE;
+    };
+
+    let functions = ast
+        .iter()
+        .filter_map(|node| match node {
+            Node::Function {
+                name, params, doc, ..
+            } => Some((name, params, doc)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if functions.is_empty() {
+        println!("{}: 関数は定義されていません", input.name());
+    }
+    for (i, (name, params, doc)) in functions.into_iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!(
+            "{} {}（{}）",
+            dictionary.spelling(&Keyword::Function),
+            name,
+            params.join("、")
+        );
+        for line in doc.iter().flat_map(|doc| doc.lines()) {
+            println!("    {}", line);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Reads and parses the program, reporting any error. Returns the source, the tree
+/// and the dictionary its keywords are read in: the one its pragma selects, or
+/// `keywords` without one.
+fn load(
+    input: &Input,
+    color: bool,
+    format: MessageFormat,
+    indentation: &Indentation,
+    keywords: &'static Dictionary,
+) -> Option<(String, Ast, &'static Dictionary)> {
+    let src = match read_source(input) {
+        Ok(src) => src,
+        Err(error) => {
+            eprintln!(
+                "shikibu: {} を読み込めませんでした: {}",
+                input.name(),
+                error
+            );
+            return None;
+        }
+    };
+
+    let name = input.name();
+    let dictionary = match Dictionary::select(&src, Some(name.as_str()), keywords) {
+        Ok(dictionary) => dictionary,
+        Err(error) => {
+            report(&src, Diagnostic::from(&error), color, format, indentation);
+            return None;
+        }
+    };
+    let cst = match Cst::parse(&src, Some(name.as_str()), indentation, dictionary) {
+        Ok(cst) => cst,
+        Err(error) => {
+            report(&src, Diagnostic::from(&error), color, format, indentation);
+            return None;
+        }
+    };
+    let (ast, errors) = cst.lower();
+    if errors.is_empty() {
+        Some((src, ast, dictionary))
+    } else {
+        for error in &errors {
+            report(&src, Diagnostic::from(error), color, format, indentation);
+        }
+        None
+    }
+}
+
+/// Prints a diagnostic to the standard error, with an excerpt of `src` for people
+/// or as a line of JSON for tools.
+pub fn report(
+    src: &str,
+    diagnostic: Diagnostic,
+    color: bool,
+    format: MessageFormat,
+    indentation: &Indentation,
+) {
+    match format {
+        MessageFormat::Human => eprint!(
+            "{}",
+            Renderer::new(src, color, indentation.tab_width).render(&diagnostic)
+        ),
+        MessageFormat::Json => eprintln!("{}", Json::from(&diagnostic)),
+    }
+}
+
+fn read_source(input: &Input) -> io::Result<String> {
+    match input {
+        Input::File(path) => read_to_string(path),
+        Input::Stdin => {
+            let mut src = String::new();
+            io::stdin().read_to_string(&mut src)?;
+            Ok(src)
+        }
+        Input::Inline(code) => Ok(code.clone()),
+    }
 }
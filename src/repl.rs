@@ -0,0 +1,160 @@
+use crate::interpreter::{
+    ast::Ast,
+    diagnostic::Diagnostic,
+    error::{SyntaxError, SyntaxErrorKind},
+    tokens::{dictionary::Dictionary, indentation::Indentation, scan, Tokens},
+    Interpreter,
+};
+use crate::{cli::MessageFormat, report};
+use std::io::{self, BufRead, Write};
+
+const PROMPT: &str = ">>> ";
+const CONTINUATION_PROMPT: &str = "... ";
+
+/// Starts the interactive mode (対話モード).
+///
+/// Lines are collected until the input forms a complete statement: a line that
+/// opens a block (`なら`, `くりかえし`, `関数`, ...) keeps reading with the
+/// continuation prompt until the block dedents back to the top level or an
+/// empty line is entered. A multi-line string, an open `（` and a line ending with
+/// `￥` keep reading until the statement is complete.
+///
+/// Keywords are read in `dictionary` throughout the session.
+pub fn start(
+    color: bool,
+    indentation: &Indentation,
+    dictionary: &'static Dictionary,
+) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut interpreter = Interpreter::interactive(dictionary);
+
+    println!(
+        "式部 {} 対話モード（終了するには Ctrl-D）",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    loop {
+        let Some(source) = read_statement(&mut lines, indentation, dictionary)? else {
+            println!();
+            return Ok(());
+        };
+        if source.trim().is_empty() {
+            continue;
+        }
+
+        let tokens = match Tokens::new(source.as_str(), None, indentation, dictionary) {
+            Ok(tokens) => tokens,
+            Err(error) => {
+                report(
+                    &source,
+                    Diagnostic::from(&error),
+                    color,
+                    MessageFormat::Human,
+                    indentation,
+                );
+                continue;
+            }
+        };
+        let ast = match Ast::try_from(tokens) {
+            Ok(ast) => ast,
+            Err(errors) => {
+                for error in &errors {
+                    report(
+                        &source,
+                        Diagnostic::from(error),
+                        color,
+                        MessageFormat::Human,
+                        indentation,
+                    );
+                }
+                continue;
+            }
+        };
+        if let Err(error) = interpreter.execute(ast) {
+            report(
+                &source,
+                Diagnostic::from(&error),
+                color,
+                MessageFormat::Human,
+                indentation,
+            );
+        }
+    }
+}
+
+/// Reads lines until they form a complete statement. Returns `None` at the end of input.
+fn read_statement(
+    lines: &mut impl Iterator<Item = io::Result<String>>,
+    indentation: &Indentation,
+    dictionary: &'static Dictionary,
+) -> io::Result<Option<String>> {
+    let mut source = String::new();
+    let mut prompt = PROMPT;
+
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(if source.is_empty() {
+                None
+            } else {
+                Some(source)
+            });
+        };
+        source.push_str(&line);
+        source.push('\n');
+
+        if !continues(&source, &line, indentation, dictionary)
+            && (line.trim().is_empty()
+                || !(is_indented(&line) || opens_block(&line, indentation, dictionary)))
+        {
+            return Ok(Some(source));
+        }
+        prompt = CONTINUATION_PROMPT;
+    }
+}
+
+fn is_indented(line: &str) -> bool {
+    line.starts_with([' ', '　', '\t'])
+}
+
+fn opens_block(line: &str, indentation: &Indentation, dictionary: &'static Dictionary) -> bool {
+    Tokens::new(line, None, indentation, dictionary).is_ok_and(|tokens| tokens.opens_block())
+}
+
+/// Returns `true` if the statement in `source` goes on to the next line: it ends
+/// inside a `”””` or `『』` string, inside `（`, `「` or `［`, or with `￥`.
+fn continues(
+    source: &str,
+    line: &str,
+    indentation: &Indentation,
+    dictionary: &'static Dictionary,
+) -> bool {
+    if line.trim_end().ends_with(['\\', '￥']) {
+        return true;
+    }
+    let (tokens, errors) = scan(source, None, dictionary);
+    if !errors.is_empty() {
+        return errors.iter().any(|error| {
+            error.kind() == SyntaxErrorKind::UnterminatedString
+                && opens_multiline_string(source, error)
+        });
+    }
+    Tokens::from_scanned(source, tokens, errors, indentation, dictionary)
+        .is_err_and(|error| error.kind() == SyntaxErrorKind::UnclosedBracket)
+}
+
+/// Returns `true` if `error`, an unterminated string, points at a `”””` or `『`.
+fn opens_multiline_string(source: &str, error: &SyntaxError) -> bool {
+    let Some(span) = &error.span else {
+        return false;
+    };
+    let opening = &source[span.start.byte..];
+    let quotes = opening
+        .chars()
+        .take_while(|c| matches!(c, '"' | '”'))
+        .count();
+    quotes >= 3 || opening.starts_with('『')
+}